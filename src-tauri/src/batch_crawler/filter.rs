@@ -0,0 +1,97 @@
+use serde::Deserialize;
+
+use super::BatchItem;
+
+/// 批量下载前的筛选条件，所有条件之间为“与”关系；
+/// 列表页未提供的信息（页数、语言）视为满足条件
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BatchFilter {
+    /// 只抓取列表的这些页（从 1 开始，第 1 页为给定的列表地址），结束页包含在内
+    pub start_page: Option<usize>,
+    pub end_page: Option<usize>,
+    /// 最多保留的条目数（按列表顺序），抓取到足够的条目后不再翻页
+    pub max_count: Option<usize>,
+    pub min_pages: Option<usize>,
+    pub max_pages: Option<usize>,
+    /// 只保留这些语言，例如 `chinese`（不区分大小写）
+    pub languages: Vec<String>,
+    /// 标题包含任一关键字时排除（不区分大小写）
+    pub exclude_keywords: Vec<String>,
+}
+
+/// 按条件筛选列表条目，保持原有顺序
+pub fn apply(items: Vec<BatchItem>, filter: &BatchFilter) -> Vec<BatchItem> {
+    items
+        .into_iter()
+        .filter(|item| matches(item, filter))
+        .take(filter.max_count.unwrap_or(usize::MAX))
+        .collect()
+}
+
+/// 条目是否满足页数、语言与关键字条件（不考虑数量上限）
+pub fn matches(item: &BatchItem, filter: &BatchFilter) -> bool {
+    let lowered = |values: &[String]| -> Vec<String> {
+        values
+            .iter()
+            .map(|v| v.trim().to_lowercase())
+            .filter(|v| !v.is_empty())
+            .collect()
+    };
+    let languages = lowered(&filter.languages);
+    let excluded = lowered(&filter.exclude_keywords);
+
+    let pages = item.page_count;
+    let title = item.title.as_deref().unwrap_or_default().to_lowercase();
+    filter.min_pages.is_none_or(|min| pages.is_none_or(|p| p >= min))
+        && filter.max_pages.is_none_or(|max| pages.is_none_or(|p| p <= max))
+        && (languages.is_empty()
+            || item.language.as_deref().is_none_or(|l| languages.contains(&l.to_lowercase())))
+        && !excluded.iter().any(|k| title.contains(k))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(url: &str, title: &str, pages: Option<usize>, language: Option<&str>) -> BatchItem {
+        BatchItem {
+            url: url.to_string(),
+            title: Some(title.to_string()),
+            page_count: pages,
+            language: language.map(|l| l.to_string()),
+            ..BatchItem::default()
+        }
+    }
+
+    #[test]
+    fn filters_by_pages_language_and_keywords() {
+        let items = vec![
+            item("a", "Short", Some(8), Some("chinese")),
+            item("b", "Normal", Some(30), Some("Chinese")),
+            item("c", "Unknown pages", None, None),
+            item("d", "English one", Some(30), Some("english")),
+            item("e", "Normal [AI Generated]", Some(30), Some("chinese")),
+        ];
+        let filter = BatchFilter {
+            min_pages: Some(10),
+            languages: vec!["chinese".to_string()],
+            exclude_keywords: vec!["ai generated".to_string()],
+            ..BatchFilter::default()
+        };
+
+        let urls: Vec<String> = apply(items, &filter).into_iter().map(|i| i.url).collect();
+
+        assert_eq!(urls, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn max_count_keeps_listing_order() {
+        let items = (0..5).map(|i| item(&i.to_string(), "t", None, None)).collect();
+        let filter = BatchFilter { max_count: Some(2), ..BatchFilter::default() };
+
+        let urls: Vec<String> = apply(items, &filter).into_iter().map(|i| i.url).collect();
+
+        assert_eq!(urls, vec!["0", "1"]);
+    }
+}
@@ -1,25 +1,145 @@
+use serde::{Deserialize, Serialize};
 use url::Url;
 
 use crate::progress::ProgressReporter;
 use crate::request::Client;
 
 pub mod factory;
+pub mod filter;
 pub mod parsers;
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
+use filter::BatchFilter;
+
+/// 列表页中的一个画廊，字段尽量从列表页本身获取，供入队前预览与筛选
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchItem {
+    pub url: String,
+    pub title: Option<String>,
+    pub thumbnail: Option<String>,
+    pub page_count: Option<usize>,
+    pub language: Option<String>,
+}
+
+impl BatchItem {
+    /// 列表只提供链接时使用
+    pub fn from_url(url: String) -> Self {
+        Self { url, ..Self::default() }
+    }
+}
+
+/// 列表抓取范围：只抓取指定的页码范围，满足筛选条件的条目足够后不再翻页
+#[derive(Debug, Clone, Default)]
+pub struct ListingLimit {
+    /// 起始页（从 1 开始，第 1 页为给定的列表地址）
+    pub start_page: usize,
+    /// 结束页（包含），为空表示直到最后一页
+    pub end_page: Option<usize>,
+    /// 满足 `filter` 的条目达到该数量后停止
+    pub max_items: Option<usize>,
+    pub filter: BatchFilter,
+    /// 已知画廊（`subscription::seen_key`）；列表按新到旧排列，某页全部已知时不再翻页
+    pub known: HashSet<String>,
+}
+
+impl ListingLimit {
+    pub fn from_filter(filter: &BatchFilter) -> Self {
+        Self {
+            start_page: filter.start_page.unwrap_or(1).max(1),
+            end_page: filter.end_page,
+            max_items: filter.max_count,
+            filter: filter.clone(),
+            known: HashSet::new(),
+        }
+    }
+
+    /// 该页的条目是否全部已知
+    pub fn all_known(&self, page_items: &[BatchItem]) -> bool {
+        !self.known.is_empty()
+            && !page_items.is_empty()
+            && page_items.iter().all(|item| self.known.contains(&crate::subscription::seen_key(&item.url)))
+    }
+
+    pub fn first_page(&self) -> usize {
+        self.start_page.max(1)
+    }
+
+    /// 限制最多抓取 `max_pages` 页，避免分页器异常时无限翻页
+    pub fn with_page_cap(&self, max_pages: usize) -> Self {
+        let cap = self.first_page() + max_pages.max(1) - 1;
+        Self { end_page: Some(self.end_page.map_or(cap, |end| end.min(cap))), ..self.clone() }
+    }
+
+    /// 第 `page` 页之后是否还需要继续翻页
+    pub fn wants_more(&self, page: usize, items: &[BatchItem]) -> bool {
+        if self.end_page.is_some_and(|end| page >= end) {
+            return false;
+        }
+        match self.max_items {
+            Some(max) => items.iter().filter(|item| filter::matches(item, &self.filter)).count() < max,
+            None => true,
+        }
+    }
+}
+
+/// 沿分页器的下一页链接逐页抓取：`fetch_page` 返回 (该页条目, 下一页地址)。
+/// 起始页之前的页面只用于翻页，条目按链接去重并保持顺序
+pub async fn crawl_pages<F, Fut>(
+    start_url: &str,
+    limit: &ListingLimit,
+    delay: std::time::Duration,
+    mut fetch_page: F,
+) -> anyhow::Result<Vec<BatchItem>>
+where
+    F: FnMut(String) -> Fut,
+    Fut: core::future::Future<Output = anyhow::Result<(Vec<BatchItem>, Option<String>)>>,
+{
+    let mut items: Vec<BatchItem> = Vec::new();
+    let mut seen = HashSet::new();
+    let mut visited = HashSet::new();
+    let mut current_url = start_url.to_string();
+    let mut page = 1;
+
+    while visited.insert(current_url.clone()) {
+        let (page_items, next_page) = fetch_page(current_url.clone()).await?;
+        if page >= limit.first_page() {
+            let all_known = limit.all_known(&page_items);
+            items.extend(page_items.into_iter().filter(|item| seen.insert(item.url.clone())));
+            if all_known || !limit.wants_more(page, &items) {
+                break;
+            }
+        }
+
+        match next_page {
+            Some(next_url) if !next_url.is_empty() => {
+                current_url = next_url;
+                page += 1;
+                // 简单的延迟，避免请求过于频繁
+                tokio::time::sleep(delay).await;
+            }
+            _ => break,
+        }
+    }
+
+    Ok(items)
+}
+
 /// 批量解析器接口
 pub trait BatchCrawler: Send + Sync {
 
-    /// 从列表页面提取所有漫画链接
-    fn extract_manga_links<'a>(
+    /// 从列表页面提取画廊（链接及列表页上可见的信息），按 `limit` 限定抓取的页码和数量
+    fn extract_manga_items<'a>(
         &'a self,
         client: &'a Client,
         url: &'a str,
+        limit: &'a ListingLimit,
         reporter: Option<Arc<dyn ProgressReporter>>,
         app_state: Option<&'a crate::AppState>,
     ) -> core::pin::Pin<
-        Box<dyn core::future::Future<Output = anyhow::Result<Vec<String>>> + Send + 'a>,
+        Box<dyn core::future::Future<Output = anyhow::Result<Vec<BatchItem>>> + Send + 'a>,
     >;
 }
 
@@ -32,13 +152,14 @@ fn ensure_builtin_registered() {
     });
 }
 
-/// 自动选择批量解析器并提取链接
-pub async fn extract_manga_links_auto(
+/// 自动选择批量解析器并提取画廊预览信息
+pub async fn extract_manga_items_auto(
     client: &Client,
     url: &str,
+    limit: &ListingLimit,
     reporter: Option<Arc<dyn ProgressReporter>>,
     app_state: Option<&crate::AppState>,
-) -> anyhow::Result<Vec<String>> {
+) -> anyhow::Result<Vec<BatchItem>> {
     ensure_builtin_registered();
     let parsed = url
         .parse::<Url>()
@@ -47,8 +168,66 @@ pub async fn extract_manga_links_auto(
 
     if let Some(site_type) = factory::detect_site_type_by_host(&host) {
         if let Some(crawler) = factory::create_for_site(site_type) {
-            return crawler.extract_manga_links(client, url, reporter, app_state).await;
+            return crawler.extract_manga_items(client, url, limit, reporter, app_state).await;
         }
     }
     anyhow::bail!("未匹配到任何批量解析器，请检查 URL 或稍后重试")
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(n: usize) -> (Vec<BatchItem>, Option<String>) {
+        let items = (0..3).map(|i| BatchItem::from_url(format!("{}-{}", n, i))).collect();
+        (items, Some(format!("page-{}", n + 1)))
+    }
+
+    #[tokio::test]
+    async fn crawl_stops_at_item_limit_and_end_page() {
+        let fetched = std::sync::Mutex::new(Vec::new());
+        let fetch = |url: String| {
+            let n: usize = url.trim_start_matches("page-").parse().unwrap();
+            fetched.lock().unwrap().push(n);
+            async move { Ok(page(n)) }
+        };
+
+        let limit = ListingLimit { start_page: 2, max_items: Some(4), ..ListingLimit::default() };
+        let items = crawl_pages("page-1", &limit, std::time::Duration::ZERO, fetch).await.unwrap();
+
+        assert_eq!(*fetched.lock().unwrap(), vec![1, 2, 3]);
+        assert_eq!(items.first().map(|i| i.url.as_str()), Some("2-0"));
+        assert_eq!(items.len(), 6);
+
+        fetched.lock().unwrap().clear();
+        let limit = ListingLimit { end_page: Some(2), ..ListingLimit::default() };
+        let items = crawl_pages("page-1", &limit, std::time::Duration::ZERO, fetch).await.unwrap();
+
+        assert_eq!(*fetched.lock().unwrap(), vec![1, 2]);
+        assert_eq!(items.len(), 6);
+    }
+
+    #[tokio::test]
+    async fn crawl_stops_after_a_page_of_known_items() {
+        let fetched = std::sync::Mutex::new(Vec::new());
+        let fetch = |url: String| {
+            let n: usize = url.trim_start_matches("page-").parse().unwrap();
+            fetched.lock().unwrap().push(n);
+            async move { Ok(page(n)) }
+        };
+        let known = (0..3).map(|i| format!("2-{}", i)).collect();
+
+        let limit = ListingLimit { known, ..ListingLimit::default() };
+        crawl_pages("page-1", &limit, std::time::Duration::ZERO, fetch).await.unwrap();
+
+        assert_eq!(*fetched.lock().unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn page_cap_counts_from_start_page() {
+        let limit = ListingLimit { start_page: 3, ..ListingLimit::default() };
+
+        assert_eq!(limit.with_page_cap(5).end_page, Some(7));
+        assert_eq!(ListingLimit { end_page: Some(4), ..limit }.with_page_cap(5).end_page, Some(4));
+    }
+}
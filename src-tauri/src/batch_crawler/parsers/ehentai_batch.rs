@@ -1,10 +1,13 @@
-use crate::batch_crawler::BatchCrawler;
+use crate::batch_crawler::{crawl_pages, BatchCrawler, BatchItem, ListingLimit};
 use crate::request::Client;
 use crate::batch_crawler::parsers::common::RequestContext;
 use crate::config::service::ConfigService;
 use reqwest::header::{HeaderMap, COOKIE};
+use once_cell::sync::Lazy;
 use std::sync::Arc;
 
+static PAGES_RE: Lazy<regex::Regex> = Lazy::new(|| regex::Regex::new(r"^(\d+) pages?$").unwrap());
+
 pub struct EhentaiBatchCrawler;
 
 impl EhentaiBatchCrawler {
@@ -12,91 +15,31 @@ impl EhentaiBatchCrawler {
         Self
     }
 
-    /// 从单个页面提取漫画链接
-    async fn extract_links_from_page(
-        &self,
-        request_ctx: &RequestContext,
-        url: &str,
-    ) -> anyhow::Result<(Vec<String>, Option<String>)> {
-        let html = request_ctx.fetch_html(url).await?;
-        let doc = scraper::Html::parse_document(&html);
-
-        // 提取漫画链接: table.gltc tr td.gl3c.glname > a
-        let mut manga_links = Vec::new();
-        if let Ok(sel_table) = scraper::Selector::parse("table.gltc tr") {
-            for tr in doc.select(&sel_table) {
-                if let Ok(sel_gl3c) = scraper::Selector::parse("td.gl3c.glname a") {
-                    for a in tr.select(&sel_gl3c) {
-                        if let Some(href) = a.value().attr("href") {
-                            if !href.is_empty() {
-                                manga_links.push(href.to_string());
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        // 检查下一页链接: #dnext a
-        let next_page = if let Ok(sel_dnext) = scraper::Selector::parse("#dnext a") {
-            doc.select(&sel_dnext)
-                .next()
-                .and_then(|a| a.value().attr("href"))
-                .map(|href| href.to_string())
-        } else {
-            None
-        };
-
-        Ok((manga_links, next_page))
-    }
-
-    /// 递归获取所有页面的漫画链接
+    /// 沿下一页链接获取列表范围内的漫画链接
     async fn extract_all_links(
         &self,
         request_ctx: &RequestContext,
         start_url: &str,
-    ) -> anyhow::Result<Vec<String>> {
-        let mut all_links = Vec::new();
-        let mut current_url = start_url.to_string();
-
-        loop {
-
-            let (mut page_links, next_page) = self.extract_links_from_page(
-                request_ctx,
-                &current_url,
-            ).await?;
-
-            all_links.append(&mut page_links);
-
-            match next_page {
-                Some(next_url) if !next_url.is_empty() => {
-                    current_url = next_url;
-                    // 简单的延迟，避免请求过于频繁
-                    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                }
-                _ => break,
-            }
-        }
-
-        // 去重，保持顺序
-        {
-            let mut seen = std::collections::HashSet::new();
-            all_links.retain(|url| seen.insert(url.clone()));
-        }
-
-        Ok(all_links)
+        limit: &ListingLimit,
+    ) -> anyhow::Result<Vec<BatchItem>> {
+        crawl_pages(start_url, limit, std::time::Duration::from_millis(500), |url| async move {
+            let html = request_ctx.fetch_html(&url).await?;
+            Ok(parse_listing_page(&html))
+        })
+        .await
     }
 }
 
 impl BatchCrawler for EhentaiBatchCrawler {
-    fn extract_manga_links<'a>(
+    fn extract_manga_items<'a>(
         &'a self,
         client: &'a Client,
         url: &'a str,
+        limit: &'a ListingLimit,
         _reporter: Option<Arc<dyn crate::progress::ProgressReporter>>,
         app_state: Option<&'a crate::AppState>,
     ) -> core::pin::Pin<
-        Box<dyn core::future::Future<Output = anyhow::Result<Vec<String>>> + Send + 'a>,
+        Box<dyn core::future::Future<Output = anyhow::Result<Vec<BatchItem>>> + Send + 'a>,
     > {
         Box::pin(async move {
             // 从配置中获取 parser 配置
@@ -113,8 +56,8 @@ impl BatchCrawler for EhentaiBatchCrawler {
 
             let request_ctx = RequestContext::new(client_limited, headers);
 
-            // 提取所有页面的漫画链接
-            let manga_links = self.extract_all_links(&request_ctx, url).await?;
+            // 提取列表范围内的漫画链接
+            let manga_links = self.extract_all_links(&request_ctx, url, limit).await?;
 
             if manga_links.is_empty() {
                 anyhow::bail!("未找到任何漫画链接");
@@ -125,8 +68,91 @@ impl BatchCrawler for EhentaiBatchCrawler {
     }
 }
 
+/// 解析列表页（紧凑模式 `table.gltc`），返回 (画廊, 下一页地址)
+fn parse_listing_page(html: &str) -> (Vec<BatchItem>, Option<String>) {
+    let doc = scraper::Html::parse_document(html);
+    let sel_tr = scraper::Selector::parse("table.gltc tr").unwrap();
+    let sel_link = scraper::Selector::parse("td.gl3c.glname a").unwrap();
+    let sel_title = scraper::Selector::parse(".glink").unwrap();
+    let sel_thumb = scraper::Selector::parse("td.gl2c img").unwrap();
+    let sel_pages = scraper::Selector::parse("td.gl4c div").unwrap();
+    let sel_tag = scraper::Selector::parse("div.gt[title^=\"language:\"]").unwrap();
+
+    // 提取漫画链接: table.gltc tr td.gl3c.glname > a
+    let mut items = Vec::new();
+    for tr in doc.select(&sel_tr) {
+        let Some(href) = tr
+            .select(&sel_link)
+            .filter_map(|a| a.value().attr("href"))
+            .find(|href| !href.is_empty())
+        else {
+            continue;
+        };
+        let title = tr
+            .select(&sel_title)
+            .next()
+            .map(|n| n.text().collect::<String>().trim().to_string())
+            .filter(|s| !s.is_empty());
+        // 首屏以外的缩略图懒加载，地址在 data-src 中
+        let thumbnail = tr
+            .select(&sel_thumb)
+            .next()
+            .and_then(|img| img.value().attr("data-src").or_else(|| img.value().attr("src")))
+            .map(|s| s.to_string());
+        let page_count = tr
+            .select(&sel_pages)
+            .find_map(|div| PAGES_RE.captures(div.text().collect::<String>().trim()).and_then(|c| c[1].parse().ok()));
+        let language = tr
+            .select(&sel_tag)
+            .filter_map(|div| div.value().attr("title")?.strip_prefix("language:"))
+            .find(|l| *l != "translated" && *l != "rewrite")
+            .map(|l| l.to_string());
+        items.push(BatchItem { url: href.to_string(), title, thumbnail, page_count, language });
+    }
+
+    // 检查下一页链接: #dnext a
+    let sel_dnext = scraper::Selector::parse("#dnext a").unwrap();
+    let next_page = doc
+        .select(&sel_dnext)
+        .next()
+        .and_then(|a| a.value().attr("href"))
+        .map(|href| href.to_string());
+
+    (items, next_page)
+}
+
 pub fn register() {
     use crate::batch_crawler::factory::{register, register_host_contains};
     register("ehentai_batch", || Box::new(EhentaiBatchCrawler::new()));
     register_host_contains("ehentai_batch", vec!["e-hentai.org", "exhentai.org"]);
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_compact_listing_rows() {
+        let html = r#"
+            <table class="itg gltc"><tr><th>Category</th></tr>
+            <tr>
+                <td class="gl2c"><div class="glthumb"><div><img data-src="https://ehgt.org/t/1.jpg" src="data:image/gif;base64,R0lGODlhAQABAAAAACw="></div></div></td>
+                <td class="gl3c glname"><a href="https://e-hentai.org/g/1/abc/"><div class="glink">[Artist] Title</div>
+                    <div><div class="gt" title="language:translated">translated</div><div class="gt" title="language:chinese">chinese</div></div></a></td>
+                <td class="gl4c glhide"><div><a href="/uploader/x">x</a></div><div>24 pages</div></td>
+            </tr>
+            </table>
+            <div class="searchnav"><div><a id="dnext" href="https://e-hentai.org/?next=1">Next &gt;</a></div></div>
+        "#;
+
+        let (items, _) = parse_listing_page(html);
+
+        assert_eq!(items, vec![BatchItem {
+            url: "https://e-hentai.org/g/1/abc/".to_string(),
+            title: Some("[Artist] Title".to_string()),
+            thumbnail: Some("https://ehgt.org/t/1.jpg".to_string()),
+            page_count: Some(24),
+            language: Some("chinese".to_string()),
+        }]);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::batch_crawler::{BatchCrawler, BatchItem, ListingLimit};
+use crate::request::Client;
+use crate::batch_crawler::parsers::common::RequestContext;
+use crate::crawler::parsers::hitomi::nozomi::{self, NozomiListing};
+use crate::crawler::parsers::hitomi::utils::extract_id;
+use reqwest::header::{HeaderMap, RANGE, REFERER};
+use reqwest::StatusCode;
+use std::sync::Arc;
+
+/// 未指定结束页和数量上限时最多读取的页数
+const MAX_LISTING_PAGES: usize = 100;
+
+pub struct HitomiBatchCrawler;
+
+impl HitomiBatchCrawler {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 下载 nozomi 文件；只通过 Range 请求页码范围 `first..=last` 对应的字节
+    async fn fetch_gallery_ids(
+        &self,
+        request_ctx: &RequestContext,
+        listing: &NozomiListing,
+        first: usize,
+        last: usize,
+    ) -> anyhow::Result<Vec<u32>> {
+        let mut headers = request_ctx.headers.clone();
+        let (start, _) = nozomi::page_byte_range(first);
+        let (_, end) = nozomi::page_byte_range(last);
+        headers.insert(RANGE, format!("bytes={}-{}", start, end).parse()?);
+
+        let resp = request_ctx.client.get_with_headers_rate_limited(&listing.url, &headers).await?;
+        let status = resp.status();
+        if status == StatusCode::RANGE_NOT_SATISFIABLE {
+            // 页码超出列表范围
+            return Ok(Vec::new());
+        }
+        if !status.is_success() {
+            anyhow::bail!("状态码异常: {}", status);
+        }
+        let partial = status == StatusCode::PARTIAL_CONTENT;
+        let bytes = resp.bytes().await?;
+        let ids = nozomi::parse_nozomi(&bytes);
+
+        // 服务端忽略 Range 时返回完整列表，自行截取所需页
+        if partial {
+            return Ok(ids);
+        }
+        let take = (last + 1).saturating_sub(first) * nozomi::GALLERIES_PER_PAGE;
+        Ok(ids.into_iter().skip((first - 1) * nozomi::GALLERIES_PER_PAGE).take(take).collect())
+    }
+}
+
+/// 列表范围对应的 nozomi 页码 (起始页, 结束页)：列表地址的 `?page=` 为范围的第 1 页，
+/// 未指定范围时只取该页；列表页没有页数等信息，条目都满足筛选条件，按数量上限换算页数；
+/// 最多读取 `MAX_LISTING_PAGES` 页
+fn page_range(listing: &NozomiListing, limit: &ListingLimit) -> (usize, usize) {
+    let base = listing.page.unwrap_or(1);
+    let first = base + limit.first_page() - 1;
+    let mut last = match limit.end_page {
+        Some(end) => Some(base + end.max(limit.first_page()) - 1),
+        None => listing.page.map(|_| first),
+    };
+    if let Some(max) = limit.max_items {
+        let by_count = first + max.div_ceil(nozomi::GALLERIES_PER_PAGE).max(1) - 1;
+        last = Some(last.map_or(by_count, |l| l.min(by_count)));
+    }
+    let cap = first + MAX_LISTING_PAGES - 1;
+    (first, last.map_or(cap, |l| l.min(cap)))
+}
+
+impl BatchCrawler for HitomiBatchCrawler {
+    fn extract_manga_items<'a>(
+        &'a self,
+        client: &'a Client,
+        url: &'a str,
+        limit: &'a ListingLimit,
+        _reporter: Option<Arc<dyn crate::progress::ProgressReporter>>,
+        _app_state: Option<&'a crate::AppState>,
+    ) -> core::pin::Pin<
+        Box<dyn core::future::Future<Output = anyhow::Result<Vec<BatchItem>>> + Send + 'a>,
+    > {
+        Box::pin(async move {
+            // 单个画廊地址没有对应的列表
+            if extract_id(url).is_some() {
+                anyhow::bail!("不支持的 hitomi 列表地址: {}", url);
+            }
+            let listing = nozomi::listing_from_url(url)
+                .ok_or_else(|| anyhow::anyhow!("不支持的 hitomi 列表地址: {}", url))?;
+
+            let mut headers = HeaderMap::new();
+            headers.insert(REFERER, "https://hitomi.la/".parse()?);
+            let request_ctx = RequestContext::new(client.clone(), headers);
+
+            let (first, last) = page_range(&listing, limit);
+            let mut ids = self.fetch_gallery_ids(&request_ctx, &listing, first, last).await?;
+
+            // 去重，保持顺序
+            {
+                let mut seen = std::collections::HashSet::new();
+                ids.retain(|id| seen.insert(*id));
+            }
+
+            if ids.is_empty() {
+                anyhow::bail!("未找到任何漫画链接");
+            }
+
+            // nozomi 只有画廊 ID，标题等信息需要逐个解析画廊才能得到
+            Ok(ids.into_iter().map(|id| BatchItem::from_url(nozomi::gallery_url(id))).collect())
+        })
+    }
+}
+
+pub fn register() {
+    use crate::batch_crawler::factory::{register, register_host_contains};
+    register("hitomi_batch", || Box::new(HitomiBatchCrawler::new()));
+    register_host_contains("hitomi_batch", vec!["hitomi.la"]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_range_starts_at_listing_page_and_stops_at_item_limit() {
+        let listing = |page| NozomiListing { url: String::new(), page };
+        let limit = |start_page, end_page, max_items| ListingLimit { start_page, end_page, max_items, ..ListingLimit::default() };
+
+        assert_eq!(page_range(&listing(None), &limit(1, None, None)), (1, MAX_LISTING_PAGES));
+        assert_eq!(page_range(&listing(Some(3)), &limit(1, None, None)), (3, 3));
+        assert_eq!(page_range(&listing(Some(3)), &limit(2, Some(4), None)), (4, 6));
+        assert_eq!(page_range(&listing(None), &limit(1, None, Some(60))), (1, 3));
+        assert_eq!(page_range(&listing(None), &limit(1, Some(2), Some(60))), (1, 2));
+        assert_eq!(page_range(&listing(None), &limit(1, Some(500), None)), (1, MAX_LISTING_PAGES));
+    }
+}
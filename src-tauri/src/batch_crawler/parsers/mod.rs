@@ -1,7 +1,15 @@
 pub mod common;
 pub mod ehentai_batch;
+pub mod hitomi_batch;
+pub mod nhentai_batch;
+pub mod pixiv_batch;
+pub mod wnacg_batch;
 
 /// 注册所有批量解析器
 pub fn register_all() {
     ehentai_batch::register();
-}
\ No newline at end of file
+    hitomi_batch::register();
+    nhentai_batch::register();
+    pixiv_batch::register();
+    wnacg_batch::register();
+}
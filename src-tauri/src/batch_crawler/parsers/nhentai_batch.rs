@@ -0,0 +1,221 @@
+use crate::batch_crawler::{crawl_pages, BatchCrawler, BatchItem, ListingLimit};
+use crate::request::Client;
+use crate::batch_crawler::parsers::common::RequestContext;
+use crate::config::service::ConfigService;
+use reqwest::header::{HeaderMap, HeaderValue, COOKIE};
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+
+/// 支持批量解析的列表路径前缀
+const LISTING_PREFIXES: &[&str] = &[
+    "search", "tag", "artist", "parody", "group", "character", "language", "category", "favorites",
+];
+
+/// 单次批量解析最多翻的列表页数（每页 25 个画廊），热门标签可达上千页
+const MAX_LISTING_PAGES: usize = 100;
+
+/// nhentai.net 列表项 `data-tags` 中的语言标签 ID
+const LANGUAGE_TAG_IDS: &[(&str, &str)] = &[("6346", "japanese"), ("12227", "english"), ("29963", "chinese")];
+
+static GALLERY_RE: Lazy<regex::Regex> = Lazy::new(|| regex::Regex::new(r"^/g/(\d+)/?$").unwrap());
+
+pub struct NhentaiBatchCrawler;
+
+impl NhentaiBatchCrawler {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 逐页获取列表范围内的画廊链接，直到没有下一页或达到页数上限
+    async fn extract_all_links(
+        &self,
+        request_ctx: &RequestContext,
+        start_url: &str,
+        limit: &ListingLimit,
+    ) -> anyhow::Result<Vec<BatchItem>> {
+        let limit = limit.with_page_cap(MAX_LISTING_PAGES);
+        crawl_pages(start_url, &limit, std::time::Duration::from_millis(500), |url| async move {
+            let html = request_ctx.fetch_html(&url).await?;
+            Ok(parse_listing_page(&html, &url))
+        })
+        .await
+    }
+}
+
+impl BatchCrawler for NhentaiBatchCrawler {
+    fn extract_manga_items<'a>(
+        &'a self,
+        client: &'a Client,
+        url: &'a str,
+        limit: &'a ListingLimit,
+        _reporter: Option<Arc<dyn crate::progress::ProgressReporter>>,
+        app_state: Option<&'a crate::AppState>,
+    ) -> core::pin::Pin<
+        Box<dyn core::future::Future<Output = anyhow::Result<Vec<BatchItem>>> + Send + 'a>,
+    > {
+        Box::pin(async move {
+            if !is_listing_url(url) {
+                anyhow::bail!("不支持的 nhentai 列表地址: {}", url);
+            }
+
+            // 从配置中获取 parser 配置
+            let parser_config = app_state.map(|state| state.config.read().get_parser_config("nhentai"));
+
+            // 使用配置中的并发数（对于批量解析，使用较低的并发数）
+            let concurrency = parser_config
+                .as_ref()
+                .and_then(|config| config.base.concurrency)
+                .unwrap_or(3);
+
+            // 收藏夹需要登录 cookies
+            let cookies = parser_config
+                .and_then(|config| config.auth)
+                .and_then(|auth| auth.cookies)
+                .unwrap_or_default();
+            let mut headers = HeaderMap::new();
+            if !cookies.is_empty() {
+                headers.insert(COOKIE, HeaderValue::from_str(&cookies)?);
+            }
+
+            let request_ctx = RequestContext::new(client.with_limit(concurrency), headers);
+
+            let manga_links = self.extract_all_links(&request_ctx, url, limit).await?;
+
+            if manga_links.is_empty() {
+                anyhow::bail!("未找到任何漫画链接");
+            }
+
+            Ok(manga_links)
+        })
+    }
+}
+
+/// 搜索、标签类列表及收藏夹页面
+fn is_listing_url(url: &str) -> bool {
+    let Ok(parsed) = url::Url::parse(url) else { return false; };
+    parsed
+        .path_segments()
+        .and_then(|mut segments| segments.next())
+        .map(|first| LISTING_PREFIXES.contains(&first))
+        .unwrap_or(false)
+}
+
+/// 解析列表页，返回 (画廊, 下一页地址)
+/// 同时兼容 nhentai.net（`.gallery a.cover`）与镜像站（`.gallery_item a`）的页面结构
+fn parse_listing_page(html: &str, page_url: &str) -> (Vec<BatchItem>, Option<String>) {
+    let doc = scraper::Html::parse_document(html);
+    let base = url::Url::parse(page_url).ok();
+    let resolve = |href: &str| -> Option<url::Url> {
+        match &base {
+            Some(b) => b.join(href).ok(),
+            None => url::Url::parse(href).ok(),
+        }
+    };
+
+    let sel_gallery = scraper::Selector::parse(".gallery, .gallery_item").unwrap();
+    let sel_a = scraper::Selector::parse("a[href]").unwrap();
+    let sel_img = scraper::Selector::parse("img").unwrap();
+    let sel_caption = scraper::Selector::parse(".caption").unwrap();
+    let mut items = Vec::new();
+    for gallery in doc.select(&sel_gallery) {
+        let link = gallery
+            .select(&sel_a)
+            .filter_map(|a| resolve(a.value().attr("href")?))
+            .find_map(|mut link| {
+                let id = GALLERY_RE.captures(link.path())?.get(1)?.as_str().to_string();
+                link.set_path(&format!("/g/{}/", id));
+                link.set_query(None);
+                link.set_fragment(None);
+                Some(link)
+            });
+        let Some(link) = link else { continue; };
+        let img = gallery.select(&sel_img).next();
+        let title = gallery
+            .select(&sel_caption)
+            .next()
+            .map(|n| n.text().collect::<String>())
+            .or_else(|| img.and_then(|i| i.value().attr("alt")).map(|s| s.to_string()))
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        // 缩略图懒加载，地址在 data-src 中
+        let thumbnail = img
+            .and_then(|i| i.value().attr("data-src").or_else(|| i.value().attr("src")))
+            .and_then(resolve)
+            .map(|u| u.to_string());
+        let language = gallery.value().attr("data-tags").and_then(|tags| {
+            tags.split_whitespace()
+                .find_map(|id| LANGUAGE_TAG_IDS.iter().find(|(tag_id, _)| *tag_id == id))
+                .map(|(_, name)| name.to_string())
+        });
+        items.push(BatchItem { url: link.to_string(), title, thumbnail, page_count: None, language });
+    }
+
+    let sel_next = scraper::Selector::parse(".pagination a.next, .pagination a[rel=\"next\"]").unwrap();
+    let next_page = doc
+        .select(&sel_next)
+        .next()
+        .and_then(|a| a.value().attr("href"))
+        .and_then(resolve)
+        .map(|u| u.to_string())
+        .filter(|u| u != page_url);
+
+    (items, next_page)
+}
+
+pub fn register() {
+    use crate::batch_crawler::factory::{register, register_host_contains};
+    register("nhentai_batch", || Box::new(NhentaiBatchCrawler::new()));
+    register_host_contains("nhentai_batch", vec!["nhentai.net", "nhentai.xxx", "nhentai.to"]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_listing_urls() {
+        assert!(is_listing_url("https://nhentai.net/search/?q=full+color"));
+        assert!(is_listing_url("https://nhentai.net/artist/foo/?page=2"));
+        assert!(is_listing_url("https://nhentai.net/favorites/"));
+        assert!(!is_listing_url("https://nhentai.net/g/123456/"));
+    }
+
+    #[test]
+    fn parses_gallery_links_and_next_page() {
+        let html = r#"
+            <div class="container index-container">
+                <div class="gallery" data-tags="29963 8378"><a href="/g/111/" class="cover">
+                    <img class="lazyload" data-src="https://t3.nhentai.net/galleries/1/thumb.jpg"><div class="caption">First</div></a></div>
+                <div class="gallery" data-tags="8378"><a href="/g/222/" class="cover"><img></a></div>
+            </div>
+            <section class="pagination">
+                <a href="/tag/full-color/?page=1" class="page current">1</a>
+                <a href="/tag/full-color/?page=2" class="next"></a>
+            </section>
+        "#;
+
+        let (items, next) = parse_listing_page(html, "https://nhentai.net/tag/full-color/");
+        let links: Vec<&str> = items.iter().map(|i| i.url.as_str()).collect();
+
+        assert_eq!(links, vec!["https://nhentai.net/g/111/", "https://nhentai.net/g/222/"]);
+        assert_eq!(items[0].title.as_deref(), Some("First"));
+        assert_eq!(items[0].thumbnail.as_deref(), Some("https://t3.nhentai.net/galleries/1/thumb.jpg"));
+        assert_eq!(items[0].language.as_deref(), Some("chinese"));
+        assert_eq!(items[1].language, None);
+        assert_eq!(next.as_deref(), Some("https://nhentai.net/tag/full-color/?page=2"));
+    }
+
+    #[test]
+    fn parses_mirror_listing_without_next_page() {
+        let html = r#"
+            <div class="gallery_item"><a href="https://nhentai.xxx/g/333/"><img></a></div>
+            <div class="pagination"><a href="/artist/foo/?page=1">1</a></div>
+        "#;
+
+        let (items, next) = parse_listing_page(html, "https://nhentai.xxx/artist/foo/");
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].url, "https://nhentai.xxx/g/333/");
+        assert_eq!(next, None);
+    }
+}
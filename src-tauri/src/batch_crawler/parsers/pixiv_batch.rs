@@ -0,0 +1,284 @@
+use crate::batch_crawler::{BatchCrawler, BatchItem, ListingLimit};
+use crate::request::Client;
+use crate::batch_crawler::parsers::common::RequestContext;
+use crate::config::service::ConfigService;
+use reqwest::header::{HeaderMap, HeaderValue, COOKIE};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// 收藏接口单页最多返回 48 条，与网页上作品列表每页的数量一致
+const BOOKMARKS_PAGE_SIZE: usize = 48;
+
+/// Pixiv 列表类型
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PixivListing {
+    /// `/users/<id>`、`/users/<id>/artworks`、`/users/<id>/illustrations`、`/users/<id>/manga`
+    Artworks { user_id: String, kind: ArtworkKind },
+    /// `/users/<id>/bookmarks/artworks`，`?rest=hide` 为非公开收藏
+    Bookmarks { user_id: String, hidden: bool },
+}
+
+/// 用户作品页对应的作品类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArtworkKind {
+    /// 插画与漫画
+    All,
+    Illusts,
+    Manga,
+}
+
+impl ArtworkKind {
+    /// `/ajax/user/<id>/profile/all` 中对应的键
+    fn profile_keys(self) -> &'static [&'static str] {
+        match self {
+            ArtworkKind::All => &["illusts", "manga"],
+            ArtworkKind::Illusts => &["illusts"],
+            ArtworkKind::Manga => &["manga"],
+        }
+    }
+}
+
+pub struct PixivBatchCrawler;
+
+impl PixivBatchCrawler {
+    pub fn new() -> Self {
+        Self
+    }
+
+    async fn fetch_json(&self, request_ctx: &RequestContext, url: &str) -> anyhow::Result<Value> {
+        let resp = request_ctx.client.get_with_headers_rate_limited(url, &request_ctx.headers).await?;
+        if !resp.status().is_success() {
+            anyhow::bail!("状态码异常: {}", resp.status());
+        }
+        let json: Value = resp.json().await?;
+        if json.get("error").and_then(|e| e.as_bool()) == Some(true) {
+            let message = json.get("message").and_then(|m| m.as_str()).unwrap_or_default();
+            anyhow::bail!("Pixiv 接口返回错误: {}", message);
+        }
+        Ok(json)
+    }
+
+    /// 用户 `kind` 类型的作品，按 ID 从新到旧；接口一次返回全部 ID，按网页的分页截取页码范围
+    async fn extract_artworks(&self, request_ctx: &RequestContext, user_id: &str, kind: ArtworkKind, limit: &ListingLimit) -> anyhow::Result<Vec<BatchItem>> {
+        let url = format!("https://www.pixiv.net/ajax/user/{}/profile/all?lang=zh", user_id);
+        let json = self.fetch_json(request_ctx, &url).await?;
+        let skip = (limit.first_page() - 1) * BOOKMARKS_PAGE_SIZE;
+        let take = limit
+            .end_page
+            .map(|end| (end + 1).saturating_sub(limit.first_page()) * BOOKMARKS_PAGE_SIZE)
+            .unwrap_or(usize::MAX);
+        // 该接口只返回作品 ID
+        Ok(parse_profile_artwork_ids(&json, kind)
+            .iter()
+            .skip(skip)
+            .take(take)
+            .map(|id| BatchItem::from_url(artwork_url(id)))
+            .collect())
+    }
+
+    /// 用户的收藏作品，按 offset 翻页直到取完或达到列表范围
+    async fn extract_bookmarks(&self, request_ctx: &RequestContext, user_id: &str, hidden: bool, limit: &ListingLimit) -> anyhow::Result<Vec<BatchItem>> {
+        let rest = if hidden { "hide" } else { "show" };
+        let mut links = Vec::new();
+        let mut page = limit.first_page();
+        let mut offset = (page - 1) * BOOKMARKS_PAGE_SIZE;
+        loop {
+            let url = format!(
+                "https://www.pixiv.net/ajax/user/{}/illusts/bookmarks?tag=&offset={}&limit={}&rest={}&lang=zh",
+                user_id, offset, BOOKMARKS_PAGE_SIZE, rest
+            );
+            let json = self.fetch_json(request_ctx, &url).await?;
+            let (items, total) = parse_bookmark_page(&json);
+            let fetched = items.len();
+            let all_known = limit.all_known(&items);
+            links.extend(items);
+            offset += BOOKMARKS_PAGE_SIZE;
+            if fetched == 0 || offset >= total || all_known || !limit.wants_more(page, &links) {
+                break;
+            }
+            page += 1;
+            // 简单的延迟，避免请求过于频繁
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        }
+        Ok(links)
+    }
+}
+
+impl BatchCrawler for PixivBatchCrawler {
+    fn extract_manga_items<'a>(
+        &'a self,
+        client: &'a Client,
+        url: &'a str,
+        limit: &'a ListingLimit,
+        _reporter: Option<Arc<dyn crate::progress::ProgressReporter>>,
+        app_state: Option<&'a crate::AppState>,
+    ) -> core::pin::Pin<
+        Box<dyn core::future::Future<Output = anyhow::Result<Vec<BatchItem>>> + Send + 'a>,
+    > {
+        Box::pin(async move {
+            let listing = parse_listing_url(url)
+                .ok_or_else(|| anyhow::anyhow!("不支持的 Pixiv 列表地址: {}", url))?;
+
+            // 与单作品解析共用 pixiv 的配置（cookies）
+            let parser_config = app_state.map(|state| state.config.read().get_parser_config("pixiv"));
+            let cookies = parser_config
+                .and_then(|config| config.auth)
+                .and_then(|auth| auth.cookies)
+                .unwrap_or_default();
+
+            let mut headers = HeaderMap::new();
+            headers.insert("referer", "https://www.pixiv.net/".parse()?);
+            if !cookies.is_empty() {
+                headers.insert(COOKIE, HeaderValue::from_str(&cookies)?);
+            }
+            let request_ctx = RequestContext::new(client.with_limit(1), headers);
+
+            let mut manga_links = match &listing {
+                PixivListing::Artworks { user_id, kind } => self.extract_artworks(&request_ctx, user_id, *kind, limit).await?,
+                PixivListing::Bookmarks { user_id, hidden } => self.extract_bookmarks(&request_ctx, user_id, *hidden, limit).await?,
+            };
+
+            // 去重，保持顺序
+            {
+                let mut seen = std::collections::HashSet::new();
+                manga_links.retain(|item| seen.insert(item.url.clone()));
+            }
+
+            if manga_links.is_empty() {
+                anyhow::bail!("未找到任何作品链接（收藏列表可能需要登录 cookies）");
+            }
+
+            Ok(manga_links)
+        })
+    }
+}
+
+fn artwork_url(id: &str) -> String {
+    format!("https://www.pixiv.net/artworks/{}", id)
+}
+
+fn parse_listing_url(url: &str) -> Option<PixivListing> {
+    let parsed = url::Url::parse(url).ok()?;
+    let segments: Vec<&str> = parsed.path_segments()?.filter(|s| !s.is_empty()).collect();
+    // 兼容带语言前缀的地址，例如 `/en/users/<id>`
+    let start = segments.iter().position(|s| *s == "users")?;
+    let user_id = segments.get(start + 1)?.to_string();
+    if user_id.is_empty() || !user_id.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    match segments.get(start + 2).copied() {
+        Some("bookmarks") => {
+            let hidden = parsed.query_pairs().any(|(k, v)| k == "rest" && v == "hide");
+            Some(PixivListing::Bookmarks { user_id, hidden })
+        }
+        None | Some("artworks") => Some(PixivListing::Artworks { user_id, kind: ArtworkKind::All }),
+        Some("illustrations") => Some(PixivListing::Artworks { user_id, kind: ArtworkKind::Illusts }),
+        Some("manga") => Some(PixivListing::Artworks { user_id, kind: ArtworkKind::Manga }),
+        _ => None,
+    }
+}
+
+/// `/ajax/user/<id>/profile/all` 中 `illusts` 与 `manga` 的键即作品 ID，只取 `kind` 对应的部分
+fn parse_profile_artwork_ids(json: &Value, kind: ArtworkKind) -> Vec<String> {
+    let mut ids: Vec<u64> = kind
+        .profile_keys()
+        .iter()
+        .filter_map(|key| json.get("body")?.get(key)?.as_object())
+        .flat_map(|works| works.keys().filter_map(|id| id.parse().ok()))
+        .collect();
+    ids.sort_unstable_by(|a, b| b.cmp(a));
+    ids.dedup();
+    ids.into_iter().map(|id| id.to_string()).collect()
+}
+
+/// 收藏接口单页结果，返回 (作品, 收藏总数)；已删除的作品没有 ID，直接跳过
+fn parse_bookmark_page(json: &Value) -> (Vec<BatchItem>, usize) {
+    let body = json.get("body");
+    let total = body
+        .and_then(|b| b.get("total"))
+        .and_then(|t| t.as_u64())
+        .unwrap_or(0) as usize;
+    let items = body
+        .and_then(|b| b.get("works"))
+        .and_then(|w| w.as_array())
+        .map(|works| {
+            works
+                .iter()
+                .filter_map(|work| {
+                    let id = match work.get("id")? {
+                        Value::String(s) => s.clone(),
+                        Value::Number(n) => n.to_string(),
+                        _ => return None,
+                    };
+                    let text = |key: &str| work.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+                    Some(BatchItem {
+                        url: artwork_url(&id),
+                        title: text("title"),
+                        thumbnail: text("url"),
+                        page_count: work.get("pageCount").and_then(|v| v.as_u64()).map(|n| n as usize),
+                        language: None,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    (items, total)
+}
+
+pub fn register() {
+    use crate::batch_crawler::factory::{register, register_host_contains};
+    register("pixiv_batch", || Box::new(PixivBatchCrawler::new()));
+    register_host_contains("pixiv_batch", vec!["pixiv.net"]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_user_listing_urls() {
+        assert_eq!(
+            parse_listing_url("https://www.pixiv.net/en/users/123/artworks"),
+            Some(PixivListing::Artworks { user_id: "123".to_string(), kind: ArtworkKind::All })
+        );
+        assert_eq!(
+            parse_listing_url("https://www.pixiv.net/users/123/manga"),
+            Some(PixivListing::Artworks { user_id: "123".to_string(), kind: ArtworkKind::Manga })
+        );
+        assert_eq!(
+            parse_listing_url("https://www.pixiv.net/users/123/bookmarks/artworks?rest=hide"),
+            Some(PixivListing::Bookmarks { user_id: "123".to_string(), hidden: true })
+        );
+        assert_eq!(parse_listing_url("https://www.pixiv.net/artworks/456"), None);
+    }
+
+    #[test]
+    fn collects_profile_ids_newest_first() {
+        let json = serde_json::json!({
+            "error": false,
+            "body": { "illusts": { "100": null, "300": null }, "manga": { "200": null }, "novels": { "999": null } }
+        });
+
+        assert_eq!(parse_profile_artwork_ids(&json, ArtworkKind::All), vec!["300", "200", "100"]);
+        assert_eq!(parse_profile_artwork_ids(&json, ArtworkKind::Illusts), vec!["300", "100"]);
+        assert_eq!(parse_profile_artwork_ids(&json, ArtworkKind::Manga), vec!["200"]);
+    }
+
+    #[test]
+    fn skips_deleted_bookmarks() {
+        let json = serde_json::json!({
+            "error": false,
+            "body": { "works": [{ "id": "10", "title": "A", "pageCount": 3 }, { "id": null }, { "id": 11 }], "total": 3 }
+        });
+
+        let (items, total) = parse_bookmark_page(&json);
+
+        assert_eq!(total, 3);
+        assert_eq!(items.iter().map(|i| i.url.as_str()).collect::<Vec<_>>(), vec![
+            "https://www.pixiv.net/artworks/10",
+            "https://www.pixiv.net/artworks/11",
+        ]);
+        assert_eq!(items[0].title.as_deref(), Some("A"));
+        assert_eq!(items[0].page_count, Some(3));
+    }
+}
@@ -0,0 +1,185 @@
+use crate::batch_crawler::{crawl_pages, BatchCrawler, BatchItem, ListingLimit};
+use crate::request::Client;
+use crate::batch_crawler::parsers::common::RequestContext;
+use crate::config::service::ConfigService;
+use crate::crawler::parsers::wnacg::{parse_manga_detail_from_li, parse_wnacg_pagination, to_abs_wnacg};
+use reqwest::header::HeaderMap;
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+
+/// 单次批量解析最多翻的列表页数
+const MAX_LISTING_PAGES: usize = 100;
+
+static PAGES_RE: Lazy<regex::Regex> = Lazy::new(|| regex::Regex::new(r"(\d+)\s*[張张]").unwrap());
+
+pub struct WnacgBatchCrawler;
+
+impl WnacgBatchCrawler {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 沿着分页器的下一页链接获取列表范围内的漫画链接
+    async fn extract_all_links(
+        &self,
+        request_ctx: &RequestContext,
+        start_url: &str,
+        limit: &ListingLimit,
+    ) -> anyhow::Result<Vec<BatchItem>> {
+        let limit = limit.with_page_cap(MAX_LISTING_PAGES);
+        // wnacg 对请求频率敏感，翻页间隔稍长
+        crawl_pages(start_url, &limit, std::time::Duration::from_millis(1000), |url| async move {
+            let html = request_ctx.fetch_html(&url).await?;
+            Ok(parse_listing_page(&html, &url))
+        })
+        .await
+    }
+}
+
+impl BatchCrawler for WnacgBatchCrawler {
+    fn extract_manga_items<'a>(
+        &'a self,
+        client: &'a Client,
+        url: &'a str,
+        limit: &'a ListingLimit,
+        _reporter: Option<Arc<dyn crate::progress::ProgressReporter>>,
+        app_state: Option<&'a crate::AppState>,
+    ) -> core::pin::Pin<
+        Box<dyn core::future::Future<Output = anyhow::Result<Vec<BatchItem>>> + Send + 'a>,
+    > {
+        Box::pin(async move {
+            if url.contains("-aid-") {
+                anyhow::bail!("不支持的 wnacg 列表地址: {}", url);
+            }
+
+            // 从配置中获取 parser 配置
+            let parser_config = app_state.map(|state| state.config.read().get_parser_config("wnacg"));
+
+            // 列表页逐页请求，并发数只影响共享的请求上限
+            let concurrency = parser_config
+                .and_then(|config| config.base.concurrency)
+                .unwrap_or(3);
+
+            let mut headers = HeaderMap::new();
+            headers.insert("Referer", "https://www.wnacg.com/".parse()?);
+            let request_ctx = RequestContext::new(client.with_limit(concurrency), headers);
+
+            let manga_links = self.extract_all_links(&request_ctx, url, limit).await?;
+
+            if manga_links.is_empty() {
+                anyhow::bail!("未找到任何漫画链接");
+            }
+
+            Ok(manga_links)
+        })
+    }
+}
+
+/// 解析分类、搜索与标签列表页，返回 (画廊, 下一页地址)
+/// 列表项与画廊缩略图页使用相同的 `gallary_wrap ul li` 结构，分页也与缩略图页一致
+fn parse_listing_page(html: &str, page_url: &str) -> (Vec<BatchItem>, Option<String>) {
+    let doc = scraper::Html::parse_document(html);
+
+    let sel_li = scraper::Selector::parse(".gallary_wrap ul li").unwrap();
+    let sel_img = scraper::Selector::parse("img").unwrap();
+    let sel_info = scraper::Selector::parse(".info_col").unwrap();
+    let mut items = Vec::new();
+    for li in doc.select(&sel_li) {
+        let Some(detail) = parse_manga_detail_from_li(&li) else { continue; };
+        if !detail.detail_url.contains("-aid-") {
+            continue;
+        }
+        let thumbnail = li
+            .select(&sel_img)
+            .next()
+            .and_then(|img| img.value().attr("src"))
+            .map(to_abs_wnacg);
+        // `.info_col` 形如 `32張照片， 創建於2024-01-01`
+        let page_count = li
+            .select(&sel_info)
+            .find_map(|n| PAGES_RE.captures(&n.text().collect::<String>()).and_then(|c| c[1].parse().ok()));
+        items.push(BatchItem {
+            url: detail.detail_url,
+            title: Some(detail.name),
+            thumbnail,
+            page_count,
+            language: None,
+        });
+    }
+
+    // 分页器中当前页为 `span.thispage`，下一页即分页地址列表中的下一项
+    let sel_current = scraper::Selector::parse(".paginator .thispage").unwrap();
+    let current = doc
+        .select(&sel_current)
+        .next()
+        .and_then(|n| n.text().collect::<String>().trim().parse::<usize>().ok())
+        .unwrap_or(1);
+    let next_page = parse_wnacg_pagination(&doc, page_url).into_iter().nth(current);
+
+    (items, next_page)
+}
+
+pub fn register() {
+    use crate::batch_crawler::factory::{register, register_host_contains};
+    register("wnacg_batch", || Box::new(WnacgBatchCrawler::new()));
+    register_host_contains("wnacg_batch", vec!["wnacg.com"]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_category_listing_and_next_page() {
+        let html = r#"
+            <div class="gallary_wrap"><ul class="cc">
+                <li class="li gallary_item">
+                    <div class="pic_box"><a href="/photos-index-aid-111.html" title="A"><img src="//t.wnacg.com/a.jpg"></a></div>
+                    <div class="info"><div class="title"><a href="/photos-index-aid-111.html">A</a></div>
+                        <div class="info_col">32張照片， 創建於2024-01-01</div></div>
+                </li>
+                <li class="li gallary_item">
+                    <div class="pic_box"><a href="https://www.wnacg.com/photos-index-aid-222.html"><img></a></div>
+                    <div class="info"><div class="title"><a href="https://www.wnacg.com/photos-index-aid-222.html">B</a></div></div>
+                </li>
+                <li class="li gallary_item"><div class="pic_box"><a href="/albums-index-cate-1.html">ad</a></div></li>
+            </ul></div>
+            <div class="f_left paginator">
+                <span class="thispage">1</span>
+                <a href="/albums-index-page-2-cate-5.html">2</a>
+                <span class="next"><a href="/albums-index-page-2-cate-5.html">後頁&gt;</a></span>
+            </div>
+        "#;
+
+        let (items, next) = parse_listing_page(html, "https://www.wnacg.com/albums-index-cate-5.html");
+        let links: Vec<String> = items.iter().map(|i| i.url.clone()).collect();
+
+        assert_eq!(links, vec![
+            "https://www.wnacg.com/photos-index-aid-111.html".to_string(),
+            "https://www.wnacg.com/photos-index-aid-222.html".to_string(),
+        ]);
+        assert_eq!(items[0].title.as_deref(), Some("A"));
+        assert_eq!(items[0].thumbnail.as_deref(), Some("https://t.wnacg.com/a.jpg"));
+        assert_eq!(items[0].page_count, Some(32));
+        assert_eq!(next.as_deref(), Some("https://www.wnacg.com/albums-index-page-2-cate-5.html"));
+    }
+
+    #[test]
+    fn last_listing_page_has_no_next_link() {
+        let html = r#"<div class="paginator"><a href="/albums-index-page-2-cate-5.html">2</a><span class="thispage">3</span><span class="next">後頁&gt;</span></div>"#;
+
+        let (items, next) = parse_listing_page(html, "https://www.wnacg.com/albums-index-page-3-cate-5.html");
+
+        assert!(items.is_empty());
+        assert_eq!(next, None);
+    }
+
+    #[test]
+    fn search_listing_pages_use_query_parameter() {
+        let html = r#"<div class="paginator"><span class="thispage">1</span><a href="/search/?q=x&p=2">2</a></div>"#;
+
+        let (_, next) = parse_listing_page(html, "https://www.wnacg.com/search/?q=x");
+
+        assert_eq!(next.as_deref(), Some("https://www.wnacg.com/search/?q=x&p=2"));
+    }
+}
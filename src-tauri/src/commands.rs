@@ -1,4 +1,4 @@
-use tauri::State;
+use tauri::{Emitter, State};
 
 use crate::AppState;
 use crate::config::service::ConfigService;
@@ -101,6 +101,58 @@ pub fn config_set_max_concurrent_tasks(state: State<'_, AppState>, max: usize) -
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub fn config_get_export_config(state: State<AppState>) -> Result<crate::config::ExportConfig, String> {
+    Ok(state.config.read().get_export_config())
+}
+
+#[tauri::command]
+pub fn config_set_export_config(state: State<'_, AppState>, export: crate::config::ExportConfig) -> Result<bool, String> {
+    state.config.write().set_export_config(export)
+        .map(|_| true)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn config_get_duplicate_policy(state: State<AppState>) -> Result<crate::config::DuplicatePolicy, String> {
+    Ok(state.config.read().get_duplicate_policy())
+}
+
+#[tauri::command]
+pub fn config_set_duplicate_policy(state: State<'_, AppState>, policy: crate::config::DuplicatePolicy) -> Result<bool, String> {
+    state.config.write().set_duplicate_policy(policy)
+        .map(|_| true)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn config_get_connection_config(state: State<AppState>) -> Result<crate::config::ConnectionConfig, String> {
+    Ok(state.config.read().get_connection_config())
+}
+
+/// 保存连接上限并立即应用到所有任务
+#[tauri::command]
+pub fn config_set_connection_config(state: State<'_, AppState>, connections: crate::config::ConnectionConfig) -> Result<bool, String> {
+    state.config.write().set_connection_config(connections.clone())
+        .map_err(|e| e.to_string())?;
+    crate::request::scheduler::connection_scheduler().set_config(connections);
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn config_get_bandwidth_config(state: State<AppState>) -> Result<crate::config::BandwidthConfig, String> {
+    Ok(state.config.read().get_bandwidth_config())
+}
+
+/// 保存下载限速配置，正在下载的任务立即按新配置限速
+#[tauri::command]
+pub fn config_set_bandwidth_config(state: State<'_, AppState>, bandwidth: crate::config::BandwidthConfig) -> Result<bool, String> {
+    crate::download::bandwidth::validate_config(&bandwidth).map_err(|e| e.to_string())?;
+    state.config.write().set_bandwidth_config(bandwidth.clone())
+        .map_err(|e| e.to_string())?;
+    crate::download::bandwidth::bandwidth_limiter().set_config(bandwidth);
+    Ok(true)
+}
 
 #[tauri::command]
 pub fn config_get_libraries(state: State<AppState>) -> Result<Vec<String>, String> {
@@ -175,10 +227,92 @@ pub fn library_get_manga_images(
     mgr.get_manga_images(&path).map_err(|e| e.to_string())
 }
 
+/// 增量刷新书库索引，`force` 为 true 时忽略修改时间全部重新读取
 #[tauri::command]
-pub fn library_delete_manga(_state: State<AppState>, path: String) -> Result<bool, String> {
+pub async fn library_index_refresh(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    force: Option<bool>,
+) -> Result<library::index::RefreshStats, String> {
+    let roots = state.config.read().get_libraries();
+    let index = state.library_index.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut history_manager = history::Manager::default();
+        let download_times = match history_manager.set_dir_from_app(&app) {
+            Ok(()) => library::index::download_times_from_history(&history_manager.get_history()),
+            Err(_) => Default::default(),
+        };
+        // 在副本上扫描，避免长时间持有写锁阻塞查询
+        let mut snapshot = index.read().clone();
+        let stats = snapshot.refresh(&roots, force.unwrap_or(false), &download_times);
+        *index.write() = snapshot;
+        stats
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 从索引读取所有书库中的漫画，索引为空时先构建一次
+#[tauri::command]
+pub async fn library_index_get_all(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<library::index::IndexEntry>, String> {
+    let roots = state.config.read().get_libraries();
+    if state.library_index.read().is_empty() {
+        library_index_refresh(app, state.clone(), None).await?;
+    }
+    Ok(state.library_index.read().entries(&roots))
+}
+
+/// 按标题、标签、作者、来源站点、页数和入库时间搜索书库（基于索引）
+#[tauri::command]
+pub async fn library_search(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    query: library::search::SearchQuery,
+) -> Result<library::search::SearchResult, String> {
+    let entries = library_index_get_all(app, state).await?;
+    library::search::search(entries, &query).map_err(|e| e.to_string())
+}
+
+/// 通过封面和采样页的感知哈希查找书库中的疑似重复漫画
+/// 进度通过 `library:dedupe-progress` 事件上报
+#[tauri::command]
+pub async fn library_find_duplicates(
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+    threshold: Option<u32>,
+) -> Result<Vec<library::phash::DuplicateGroup>, String> {
+    let roots = state.config.read().get_libraries();
+    let threshold = threshold.unwrap_or(library::phash::DEFAULT_THRESHOLD);
+    tokio::task::spawn_blocking(move || {
+        library::phash::find_duplicates(&roots, threshold, |current, total| {
+            let _ = app.emit(
+                "library:dedupe-progress",
+                serde_json::json!({"current": current, "total": total}),
+            );
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 读取单页内容为 data URL，支持压缩包内页面（`xxx.cbz!/0001.jpg`）
+#[tauri::command]
+pub async fn library_read_page(page: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || library::archive::read_page_data_url(&page))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn library_delete_manga(state: State<AppState>, path: String) -> Result<bool, String> {
     let mgr = library::Manager::default();
-    mgr.delete_manga(&path).map_err(|e| e.to_string())
+    let deleted = mgr.delete_manga(&path).map_err(|e| e.to_string())?;
+    state.library_index.write().remove(&path);
+    Ok(deleted)
 }
 
 // ---------- history ----------
@@ -270,14 +404,38 @@ pub fn task_get_status(
 }
 
 // ---------- crawler ----------
+/// 列出多章节画廊的章节；下载部分章节时在 `task_start_crawl` 中传入 `chapters`（如 `1,3-5`）
+#[tauri::command]
+pub async fn crawl_list_chapters(
+    state: State<'_, AppState>,
+    url: String,
+) -> Result<crate::crawler::ChapterList, String> {
+    let client = state.client_for_url(&url).map_err(|e| e.to_string())?;
+    let output_dir = state.config.read().get_output_dir();
+    crate::services::CrawlService::list_chapters(&client, &url, &output_dir)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 重新加载配置目录 `parsers/` 下的自定义站点规则，返回加载结果
+#[tauri::command]
+pub fn crawl_reload_parser_rules(
+    state: State<AppState>,
+) -> Result<crate::crawler::parsers::rule::RuleLoadReport, String> {
+    Ok(state.reload_parser_rules())
+}
+
 // 重构后的简化实现：使用TaskService处理所有复杂逻辑
 #[tauri::command]
 pub async fn task_start_crawl(
     state: State<'_, AppState>,
     app: tauri::AppHandle,
     url: String,
+    chapters: Option<String>,
 ) -> Result<String, String> {
-    state.task_service.start_crawl_task(url, app, &state).await
+    let chapters = crate::crawler::parse_chapter_selection(chapters.as_deref().unwrap_or(""))
+        .map_err(|e| e.to_string())?;
+    state.task_service.start_crawl_task(url, chapters, app, &state).await
         .map_err(|e| e.to_string())
 }
 
@@ -293,6 +451,147 @@ pub async fn batch_start_crawl(
         .map_err(|e| e.to_string())
 }
 
+/// 预览列表中的漫画（可按数量、页数、语言、关键字筛选），不创建任务
+#[tauri::command]
+pub async fn batch_preview(
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+    url: String,
+    filter: Option<crate::batch_crawler::filter::BatchFilter>,
+) -> Result<crate::services::batch_service::BatchPreview, String> {
+    let batch_service = crate::services::BatchService::new();
+    batch_service.preview_batch_crawl(url, filter.unwrap_or_default(), &app, &state).await
+        .map_err(|e| e.to_string())
+}
+
+/// 为预览中选中的漫画创建下载任务
+#[tauri::command]
+pub async fn batch_enqueue(
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+    source_url: String,
+    urls: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let batch_service = crate::services::BatchService::new();
+    batch_service.enqueue_batch(source_url, urls, app, &state).await
+        .map_err(|e| e.to_string())
+}
+
+// ---------- subscription ----------
+#[tauri::command]
+pub fn subscription_list(
+    state: State<AppState>,
+) -> Result<Vec<crate::subscription::Subscription>, String> {
+    Ok(state.subscriptions.read().all())
+}
+
+/// 订阅列表页；默认首次检查只记录现有画廊，`download_existing` 为 true 时立即下载；
+/// 每次检查只读取前几页，最多 `max_items` 个画廊
+#[tauri::command]
+pub fn subscription_add(
+    state: State<AppState>,
+    app: tauri::AppHandle,
+    url: String,
+    name: Option<String>,
+    interval_minutes: u64,
+    download_existing: Option<bool>,
+    max_items: Option<usize>,
+) -> Result<crate::subscription::Subscription, String> {
+    let subscription = state
+        .subscriptions
+        .write()
+        .add(url, name.unwrap_or_default(), interval_minutes, max_items)
+        .map_err(|e| e.to_string())?;
+
+    // 立即执行首次检查，不阻塞命令返回
+    let state_clone = state.inner().clone();
+    let id = subscription.id.clone();
+    let include_existing = download_existing.unwrap_or(false);
+    tauri::async_runtime::spawn(async move {
+        let _ = crate::services::SubscriptionService::check(&id, include_existing, &app, &state_clone).await;
+    });
+
+    Ok(subscription)
+}
+
+#[tauri::command]
+pub fn subscription_update(
+    state: State<AppState>,
+    id: String,
+    interval_minutes: Option<u64>,
+    enabled: Option<bool>,
+) -> Result<crate::subscription::Subscription, String> {
+    state
+        .subscriptions
+        .write()
+        .update(&id, interval_minutes, enabled)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn subscription_remove(state: State<AppState>, id: String) -> Result<bool, String> {
+    state.subscriptions.write().remove(&id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn subscription_check_now(
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+    id: String,
+) -> Result<crate::services::subscription_service::CheckResult, String> {
+    crate::services::SubscriptionService::check(&id, false, &app, &state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ---------- cookies ----------
+/// 导入浏览器导出的 cookie（Netscape `cookies.txt` 或 JSON），返回导入数量
+#[tauri::command]
+pub fn cookies_import(content: String) -> Result<usize, String> {
+    let cookies = crate::request::cookies::parse_cookie_export(&content).map_err(|e| e.to_string())?;
+    if cookies.is_empty() {
+        return Err("未找到任何 cookie".to_string());
+    }
+    crate::request::cookies::cookie_jar()
+        .import(cookies)
+        .map_err(|e| e.to_string())
+}
+
+/// 删除某个域名（含子域名）的 cookie，返回删除数量
+#[tauri::command]
+pub fn cookies_clear(domain: String) -> Result<usize, String> {
+    crate::request::cookies::cookie_jar()
+        .clear_domain(&domain)
+        .map_err(|e| e.to_string())
+}
+
+/// 各站点是否已登录（站点配置中的 cookie 与 cookie 存储合并判断）
+#[tauri::command]
+pub fn cookies_session_status(
+    state: State<AppState>,
+) -> Result<Vec<crate::request::cookies::SessionStatus>, String> {
+    let config = state.config.read();
+    Ok(crate::request::cookies::session_statuses(|site| {
+        config
+            .get_parser_config(site)
+            .auth
+            .and_then(|auth| auth.cookies)
+            .filter(|c| !c.trim().is_empty())
+    }))
+}
+
+// ---------- export ----------
+#[tauri::command]
+pub async fn export_cbz(
+    _state: State<'_, AppState>,
+    path: String,
+    delete_source: Option<bool>,
+) -> Result<String, String> {
+    crate::services::ExportService::export_cbz(path, delete_source.unwrap_or(false)).await
+        .map(|p| p.to_string_lossy().to_string())
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn task_cancel(
     state: State<AppState>,
@@ -12,6 +12,75 @@ pub struct Config {
     pub active_library: String,
     pub parser_configs: Option<std::collections::HashMap<String, parser_config::ParserConfig>>,
     pub max_concurrent_tasks: Option<usize>,
+    pub export: Option<ExportConfig>,
+    pub duplicate_policy: Option<DuplicatePolicy>,
+    pub connections: Option<ConnectionConfig>,
+    pub bandwidth: Option<BandwidthConfig>,
+}
+
+/// 发现已下载过的画廊时的处理方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicatePolicy {
+    /// 跳过，不再下载
+    #[default]
+    Skip,
+    /// 重新下载到原目录，原有页面文件先删除后重新获取
+    Redownload,
+    /// 下载到带编号后缀的新目录，例如 `标题 (2)`
+    Suffix,
+}
+
+/// 下载完成后的导出配置
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ExportConfig {
+    /// 任务完成后自动打包为 CBZ
+    pub auto_cbz: bool,
+    /// 打包成功后删除散图
+    pub delete_after_export: bool,
+}
+
+/// 所有任务共享的连接数上限
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ConnectionConfig {
+    /// 所有任务合计的最大连接数
+    pub max_connections: usize,
+    /// 单个主机的默认最大连接数
+    pub max_connections_per_host: usize,
+    /// 按主机覆盖单主机上限，键匹配主机名及其子域名
+    #[serde(default)]
+    pub host_limits: std::collections::HashMap<String, usize>,
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 24,
+            max_connections_per_host: 8,
+            host_limits: std::collections::HashMap::new(),
+        }
+    }
+}
+
+/// 下载限速配置，速率单位为字节/秒，未设置或为 0 表示不限速
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct BandwidthConfig {
+    /// 所有下载合计的速率上限
+    pub global_limit: Option<u64>,
+    /// 按站点类型（如 `hitomi`）限制该站点所有下载的合计速率
+    pub site_limits: std::collections::HashMap<String, u64>,
+    /// 按时间段覆盖全局上限，先匹配的优先
+    pub schedule: Vec<BandwidthSchedule>,
+}
+
+/// 时间段限速，例如工作时间 `09:00`-`18:00` 限制为 2 MB/s；结束时间早于开始时间表示跨午夜
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BandwidthSchedule {
+    pub start: String,
+    pub end: String,
+    /// 该时间段的全局上限，None 表示不限速
+    pub global_limit: Option<u64>,
 }
 
 impl Default for Config {
@@ -23,6 +92,10 @@ impl Default for Config {
             active_library: String::new(),
             parser_configs: None,
             max_concurrent_tasks: Some(3), // 默认最多3个并发任务
+            export: None,
+            duplicate_policy: None,
+            connections: None,
+            bandwidth: None,
         }
     }
 }
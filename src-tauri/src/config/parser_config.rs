@@ -10,7 +10,14 @@ pub struct BaseParserConfig {
     pub retry_count: Option<usize>,
     pub user_agent: Option<String>,
     pub custom_headers: HashMap<String, String>,
-    pub proxy_enabled: bool,
+    /// 是否使用全局代理，未设置时使用；只有明确设为 false 的站点才直连。
+    /// 旧版本保存的 `proxy_enabled` 总是 false 且从未生效，不再读取
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub use_proxy: Option<bool>,
+    /// 每个主机每秒最多请求数，未设置时使用站点默认值
+    pub rate_limit: Option<f64>,
+    /// 令牌桶允许的突发请求数
+    pub rate_burst: Option<u32>,
 }
 
 /// 认证相关配置
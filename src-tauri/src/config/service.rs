@@ -2,7 +2,7 @@ use std::sync::Arc;
 use std::path::PathBuf;
 use parking_lot::RwLock;
 use tauri::Manager as TauriManager;
-use crate::config::{Config, repository::{ConfigRepository, FileConfigRepository}, parser_config::{ParserConfig, ParserConfigManager}};
+use crate::config::{BandwidthConfig, Config, ConnectionConfig, DuplicatePolicy, ExportConfig, repository::{ConfigRepository, FileConfigRepository}, parser_config::{ParserConfig, ParserConfigManager}};
 
 /// 配置服务接口
 pub trait ConfigService {
@@ -20,6 +20,14 @@ pub trait ConfigService {
     fn get_all_parser_configs(&self) -> std::collections::HashMap<String, ParserConfig>;
     fn get_max_concurrent_tasks(&self) -> usize;
     fn set_max_concurrent_tasks(&mut self, max: usize) -> anyhow::Result<()>;
+    fn get_export_config(&self) -> ExportConfig;
+    fn set_export_config(&mut self, export: ExportConfig) -> anyhow::Result<()>;
+    fn get_duplicate_policy(&self) -> DuplicatePolicy;
+    fn set_duplicate_policy(&mut self, policy: DuplicatePolicy) -> anyhow::Result<()>;
+    fn get_connection_config(&self) -> ConnectionConfig;
+    fn set_connection_config(&mut self, connections: ConnectionConfig) -> anyhow::Result<()>;
+    fn get_bandwidth_config(&self) -> BandwidthConfig;
+    fn set_bandwidth_config(&mut self, bandwidth: BandwidthConfig) -> anyhow::Result<()>;
 }
 
 /// 应用配置服务实现
@@ -178,6 +186,58 @@ impl ConfigService for AppConfigService {
         config.max_concurrent_tasks = Some(max);
         self.save(&config)
     }
+
+    fn get_export_config(&self) -> ExportConfig {
+        self.load()
+            .ok()
+            .and_then(|c| c.export)
+            .unwrap_or_default()
+    }
+
+    fn set_export_config(&mut self, export: ExportConfig) -> anyhow::Result<()> {
+        let mut config = self.load()?;
+        config.export = Some(export);
+        self.save(&config)
+    }
+
+    fn get_duplicate_policy(&self) -> DuplicatePolicy {
+        self.load()
+            .ok()
+            .and_then(|c| c.duplicate_policy)
+            .unwrap_or_default()
+    }
+
+    fn set_duplicate_policy(&mut self, policy: DuplicatePolicy) -> anyhow::Result<()> {
+        let mut config = self.load()?;
+        config.duplicate_policy = Some(policy);
+        self.save(&config)
+    }
+
+    fn get_connection_config(&self) -> ConnectionConfig {
+        self.load()
+            .ok()
+            .and_then(|c| c.connections)
+            .unwrap_or_default()
+    }
+
+    fn set_connection_config(&mut self, connections: ConnectionConfig) -> anyhow::Result<()> {
+        let mut config = self.load()?;
+        config.connections = Some(connections);
+        self.save(&config)
+    }
+
+    fn get_bandwidth_config(&self) -> BandwidthConfig {
+        self.load()
+            .ok()
+            .and_then(|c| c.bandwidth)
+            .unwrap_or_default()
+    }
+
+    fn set_bandwidth_config(&mut self, bandwidth: BandwidthConfig) -> anyhow::Result<()> {
+        let mut config = self.load()?;
+        config.bandwidth = Some(bandwidth);
+        self.save(&config)
+    }
 }
 
 fn default_config_path(app: &tauri::AppHandle) -> anyhow::Result<PathBuf> {
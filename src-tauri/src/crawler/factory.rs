@@ -4,7 +4,8 @@ use std::collections::HashMap;
 
 use super::SiteParser;
 
-type ParserCtor = fn() -> Box<dyn SiteParser>;
+// 使用闭包而非函数指针，以便运行时加载的规则解析器携带各自的规则
+type ParserCtor = Box<dyn Fn() -> Box<dyn SiteParser> + Send + Sync + 'static>;
 
 pub type HostMatcher = Box<dyn Fn(&str) -> bool + Send + Sync + 'static>;
 
@@ -19,14 +20,23 @@ static PARSER_REGISTRY: Lazy<RwLock<HashMap<&'static str, ParserCtor>>> =
 static HOST_MATCHERS: Lazy<RwLock<Vec<HostMatcherEntry>>> =
     Lazy::new(|| RwLock::new(Vec::new()));
 
-pub fn register(site_type: &'static str, ctor: ParserCtor) {
-    PARSER_REGISTRY.write().insert(site_type, ctor);
+pub fn register(site_type: &'static str, ctor: impl Fn() -> Box<dyn SiteParser> + Send + Sync + 'static) {
+    PARSER_REGISTRY.write().insert(site_type, Box::new(ctor));
+    super::invalidate_gallery_keys();
+}
+
+/// 移除站点解析器及其 host 匹配器（用于重新加载规则解析器）
+pub fn unregister(site_type: &str) {
+    PARSER_REGISTRY.write().remove(site_type);
+    HOST_MATCHERS.write().retain(|entry| entry.site_type != site_type);
+    super::invalidate_gallery_keys();
 }
 
 pub fn register_host_matcher(site_type: &'static str, matcher: HostMatcher) {
     HOST_MATCHERS
         .write()
         .push(HostMatcherEntry { site_type, matcher });
+    super::invalidate_gallery_keys();
 }
 
 pub fn register_host_contains(site_type: &'static str, substrings: Vec<&'static str>) {
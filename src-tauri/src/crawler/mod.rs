@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use url::Url;
 
 use crate::progress::ProgressReporter;
@@ -21,6 +21,95 @@ pub struct ParsedGallery {
     // 推荐的下载并发数，不设置则使用默认值
     #[serde(skip)]
     pub recommended_concurrency: Option<usize>,
+    // 站点提供的画廊元数据（作者、标签、语言等），用于生成 ComicInfo.xml
+    pub metadata: GalleryMetadata,
+    // 下载完成后需要对图片做的还原处理（例如 18comic 切条打乱）
+    #[serde(skip)]
+    pub image_transform: Option<crate::download::transform::ImageTransform>,
+    // 多章节画廊的章节划分，为空表示单章节；各章节的页面按 `<章节>_<页码>` 命名保存在同一目录
+    pub chapters: Vec<Chapter>,
+}
+
+/// 章节在 `image_urls` 中对应的页面范围
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Chapter {
+    /// 从 1 开始的章节序号（跳过未选择的章节时保留原序号）
+    pub index: usize,
+    pub title: String,
+    pub start: usize,
+    pub count: usize,
+}
+
+/// 章节列表中的一项（供前端选择要下载的章节）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterInfo {
+    /// 从 1 开始的章节序号，与任务的章节选择对应
+    pub index: usize,
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    /// 画廊目录中已有该章节的页面
+    #[serde(default)]
+    pub downloaded: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterList {
+    pub title: Option<String>,
+    pub chapters: Vec<ChapterInfo>,
+}
+
+/// 章节页面文件名前缀：第 1 章的页面保存为 `001_0001.jpg`、`001_0002.jpg` ...，
+/// 按文件名排序即为阅读顺序，书库、导出和去重都按普通画廊目录处理
+pub fn chapter_page_prefix(index: usize) -> String {
+    format!("{:03}_", index)
+}
+
+/// 章节选择允许的最大序号，避免 `1-999999999` 之类的输入展开出巨大的列表
+const MAX_CHAPTER_INDEX: usize = 10_000;
+
+/// 解析章节选择参数（从 1 开始），例如 `1,3-5` -> [1, 3, 4, 5]；为空表示全部
+pub fn parse_chapter_selection(selection: &str) -> anyhow::Result<Vec<usize>> {
+    let mut indices = Vec::new();
+    for part in selection.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let (start, end) = match part.split_once('-') {
+            Some((a, b)) => (a.trim().parse::<usize>()?, b.trim().parse::<usize>()?),
+            None => {
+                let n = part.parse::<usize>()?;
+                (n, n)
+            }
+        };
+        if start == 0 || end < start {
+            anyhow::bail!("无效的章节范围: {}", part);
+        }
+        if end > MAX_CHAPTER_INDEX {
+            anyhow::bail!("章节序号不能超过 {}: {}", MAX_CHAPTER_INDEX, part);
+        }
+        indices.extend(start..=end);
+    }
+    indices.sort_unstable();
+    indices.dedup();
+    Ok(indices)
+}
+
+/// 画廊元数据，解析器尽量填充站点能提供的字段
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GalleryMetadata {
+    pub source_url: Option<String>,
+    // 原文标题（例如 e-hentai 的日文标题）
+    pub original_title: Option<String>,
+    pub artists: Vec<String>,
+    pub groups: Vec<String>,
+    pub parodies: Vec<String>,
+    pub characters: Vec<String>,
+    pub tags: Vec<String>,
+    pub language: Option<String>,
+    pub category: Option<String>,
+    pub page_count: Option<usize>,
 }
 
 // 解析器接口（统一为带 reporter 的单一方法，解析器可自由忽略 reporter）
@@ -33,6 +122,10 @@ pub trait SiteParser: Send + Sync {
     fn can_handle(&self, host: &str) -> bool {
         self.domains().iter().any(|d| host.ends_with(d))
     }
+    /// 从 URL 中提取站点内的画廊 ID，用于去重；无法识别时返回 None
+    fn gallery_id(&self, _url: &str) -> Option<String> {
+        None
+    }
     fn parse<'a>(
         &'a self,
         client: &'a Client,
@@ -42,10 +135,34 @@ pub trait SiteParser: Send + Sync {
     ) -> core::pin::Pin<
         Box<dyn core::future::Future<Output = anyhow::Result<ParsedGallery>> + Send + 'a>,
     >;
+    /// 只解析选中的章节（从 1 开始的序号，为空表示全部）；不支持章节的站点忽略选择
+    fn parse_chapters<'a>(
+        &'a self,
+        client: &'a Client,
+        url: &'a str,
+        _chapters: &'a [usize],
+        reporter: Option<Arc<dyn ProgressReporter>>,
+        app_state: Option<&'a crate::AppState>,
+    ) -> core::pin::Pin<
+        Box<dyn core::future::Future<Output = anyhow::Result<ParsedGallery>> + Send + 'a>,
+    > {
+        self.parse(client, url, reporter, app_state)
+    }
+    /// 列出多章节画廊的章节，不支持章节的站点返回空列表
+    fn list_chapters<'a>(
+        &'a self,
+        _client: &'a Client,
+        _url: &'a str,
+    ) -> core::pin::Pin<
+        Box<dyn core::future::Future<Output = anyhow::Result<ChapterList>> + Send + 'a>,
+    > {
+        Box::pin(async { Ok(ChapterList::default()) })
+    }
 }
 
 // 解析器选择（可扩展：按 host 返回特定站点解析器）
-fn ensure_builtin_registered() {
+// 自定义规则注册前也会调用，保证内置解析器的 host 匹配器排在前面、优先于规则
+pub(crate) fn ensure_builtin_registered() {
     static ONCE: std::sync::Once = std::sync::Once::new();
     ONCE.call_once(|| {
         //  parsers 子模块注册
@@ -53,10 +170,57 @@ fn ensure_builtin_registered() {
     });
 }
 
+/// URL 对应的站点类型（与解析器配置的名称一致）
+pub fn site_type_for_url(url: &str) -> Option<&'static str> {
+    ensure_builtin_registered();
+    let parsed = url.parse::<Url>().ok()?;
+    factory::detect_site_type_by_host(parsed.host_str()?)
+}
+
+/// 画廊的规范化标识 `<站点>:<画廊ID>`，同一画廊的不同 URL 形式得到相同结果
+pub fn gallery_key(url: &str) -> Option<String> {
+    if let Some(key) = GALLERY_KEYS.read().get(url) {
+        return key.clone();
+    }
+    let key = compute_gallery_key(url);
+    GALLERY_KEYS.write().insert(url.to_string(), key.clone());
+    key
+}
+
+fn compute_gallery_key(url: &str) -> Option<String> {
+    let site = site_type_for_url(url)?;
+    let parser = factory::create_for_site(site)?;
+    let id = parser.gallery_id(url)?;
+    Some(format!("{}:{}", site, id))
+}
+
+// 去重时需要对任务、历史中的每个地址求画廊标识，按 URL 缓存；解析器注册变化时清空
+static GALLERY_KEYS: once_cell::sync::Lazy<parking_lot::RwLock<std::collections::HashMap<String, Option<String>>>> =
+    once_cell::sync::Lazy::new(Default::default);
+
+pub(crate) fn invalidate_gallery_keys() {
+    GALLERY_KEYS.write().clear();
+}
+
+pub async fn list_chapters_auto(client: &Client, url: &str) -> anyhow::Result<ChapterList> {
+    ensure_builtin_registered();
+    let parsed = url
+        .parse::<Url>()
+        .map_err(|e| anyhow::anyhow!("无效的 URL: {}", e))?;
+    let host = parsed.host_str().unwrap_or("").to_string();
+    if let Some(site) = factory::detect_site_type_by_host(&host) {
+        if let Some(parser) = factory::create_for_site(site) {
+            return parser.list_chapters(client, url).await;
+        }
+    }
+    anyhow::bail!("未匹配到任何站点解析器，请检查 URL 或稍后重试")
+}
+
 // 自动选择解析器并解析
 pub async fn parse_gallery_auto(
     client: &Client,
     url: &str,
+    chapters: &[usize],
     reporter: Option<Arc<dyn ProgressReporter>>,
     app_state: Option<&crate::AppState>,
 ) -> anyhow::Result<ParsedGallery> {
@@ -67,8 +231,22 @@ pub async fn parse_gallery_auto(
     let host = parsed.host_str().unwrap_or("").to_string();
     if let Some(site) = factory::detect_site_type_by_host(&host) {
         if let Some(parser) = factory::create_for_site(site) {
-            return parser.parse(client, url, reporter, app_state).await;
+            return parser.parse_chapters(client, url, chapters, reporter, app_state).await;
         }
     }
     anyhow::bail!("未匹配到任何站点解析器，请检查 URL 或稍后重试")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_chapter_selection_ranges() {
+        assert_eq!(parse_chapter_selection("3, 1-2,2").unwrap(), vec![1, 2, 3]);
+        assert!(parse_chapter_selection("").unwrap().is_empty());
+        assert!(parse_chapter_selection("0").is_err());
+        assert!(parse_chapter_selection("5-3").is_err());
+        assert!(parse_chapter_selection("1-999999999").is_err());
+    }
+}
@@ -1,8 +1,16 @@
-use crate::crawler::{ParsedGallery, SiteParser, ProgressReporter};
+use crate::crawler::{Chapter, ChapterInfo, ChapterList, ParsedGallery, SiteParser, ProgressReporter};
 use crate::request::Client;
 use crate::progress::ProgressContext;
 use crate::crawler::parsers::common::RequestContext;
 use crate::config::service::ConfigService;
+use crate::download::transform::{ImageTransform, COMIC18_DEFAULT_SCRAMBLE_ID};
+use once_cell::sync::Lazy;
+
+static GALLERY_ID_RE: Lazy<regex::Regex> = Lazy::new(|| regex::Regex::new(r"/(?:album|photo)/(\d+)").unwrap());
+static ALBUM_PATH_RE: Lazy<regex::Regex> = Lazy::new(|| regex::Regex::new(r"/album/(\d+)[^?#]*").unwrap());
+static PHOTO_PATH_RE: Lazy<regex::Regex> = Lazy::new(|| regex::Regex::new(r"/photo/(\d+)[^?#]*").unwrap());
+static PHOTO_ID_RE: Lazy<regex::Regex> = Lazy::new(|| regex::Regex::new(r"/photo/(\d+)").unwrap());
+static SCRAMBLE_ID_RE: Lazy<regex::Regex> = Lazy::new(|| regex::Regex::new(r"var\s+scramble_id\s*=\s*(\d+)").unwrap());
 
 pub struct Comic18Parser;
 
@@ -11,12 +19,44 @@ impl Comic18Parser {
         Self
     }
 
+    /// 解析单个章节（/photo/<id>）页面，返回 (标题, 图片, scramble_id)
+    async fn parse_photo(
+        &self,
+        request_ctx: &RequestContext,
+        url: &str,
+    ) -> anyhow::Result<(Option<String>, Vec<String>, Option<u64>)> {
+        let html = request_ctx.fetch_html(url).await?;
+        let doc = scraper::Html::parse_document(&html);
+        let title = parse_title(&doc);
+        let image_urls = parse_photo_images(&doc);
+        if image_urls.is_empty() {
+            anyhow::bail!("未找到任何图片");
+        }
+        Ok((title, image_urls, parse_scramble_id(&html)))
+    }
+
+    /// 解析专辑（/album/<id>）页面，返回 (标题, 章节列表)
+    async fn fetch_album(
+        &self,
+        request_ctx: &RequestContext,
+        url: &str,
+    ) -> anyhow::Result<(Option<String>, Vec<ChapterInfo>)> {
+        let html = request_ctx.fetch_html(url).await?;
+        let doc = scraper::Html::parse_document(&html);
+        Ok((parse_title(&doc), parse_episodes(&doc, url)))
+    }
 }
 
 impl SiteParser for Comic18Parser {
     fn name(&self) -> &'static str { "18comic" }
     fn domains(&self) -> &'static [&'static str] { &["18comic.vip", "18comic.org"] }
+    fn gallery_id(&self, url: &str) -> Option<String> {
+        Some(GALLERY_ID_RE.captures(url)?.get(1)?.as_str().to_string())
+    }
     fn parse<'a>(&'a self, client: &'a Client, url: &'a str, reporter: Option<std::sync::Arc<dyn ProgressReporter>>, app_state: Option<&'a crate::AppState>) -> core::pin::Pin<Box<dyn core::future::Future<Output = anyhow::Result<ParsedGallery>> + Send + 'a>> {
+        self.parse_chapters(client, url, &[], reporter, app_state)
+    }
+    fn parse_chapters<'a>(&'a self, client: &'a Client, url: &'a str, selected: &'a [usize], reporter: Option<std::sync::Arc<dyn ProgressReporter>>, app_state: Option<&'a crate::AppState>) -> core::pin::Pin<Box<dyn core::future::Future<Output = anyhow::Result<ParsedGallery>> + Send + 'a>> {
         Box::pin(async move {
             // 创建ProgressContext
             let progress = ProgressContext::new(reporter, "18Comic".to_string());
@@ -30,52 +70,167 @@ impl SiteParser for Comic18Parser {
                 .unwrap_or(5);
             let request_ctx = RequestContext::with_concurrency(client.clone(), concurrency);
 
-            // 获取HTML内容
-            let html = request_ctx.fetch_html(url).await?;
-            let doc = scraper::Html::parse_document(&html);
-
-            let title = {
-                let sel = scraper::Selector::parse("h1").unwrap();
-                doc.select(&sel).next().map(|n| n.text().collect::<String>()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+            // 专辑页面：读取章节列表，按选择逐章解析
+            let (title, episodes) = if is_album_url(url) {
+                self.fetch_album(&request_ctx, url).await?
+            } else {
+                (None, Vec::new())
             };
 
-            let sel_img = scraper::Selector::parse(".scramble-page > img").unwrap();
-            let images: Vec<scraper::element_ref::ElementRef> = doc.select(&sel_img).collect();
-            if images.is_empty() {
-                anyhow::bail!("未找到任何图片");
-            }
-
-            // 使用ProgressContext
-            progress.update(0, images.len(), "正在解析图片链接");
-
-            let total_images = images.len();
-            let mut image_urls: Vec<String> = Vec::with_capacity(total_images);
-            for (i, img) in images.into_iter().enumerate() {
-                if let Some(src) = img.value().attr("data-original") {
-                    image_urls.push(src.to_string());
-                } else if let Some(src) = img.value().attr("src") {
-                    image_urls.push(src.to_string());
+            let (title, image_urls, scramble_id, chapters) = if episodes.len() > 1 {
+                let episodes: Vec<ChapterInfo> = episodes
+                    .into_iter()
+                    .filter(|ep| selected.is_empty() || selected.contains(&ep.index))
+                    .collect();
+                if episodes.is_empty() {
+                    anyhow::bail!("所选章节不存在");
                 }
-                progress.update(i + 1, total_images, "正在解析图片链接");
-            }
 
-            image_urls.sort();
-            image_urls.dedup();
-            if image_urls.is_empty() {
-                anyhow::bail!("未找到任何图片");
-            }
+                let total = episodes.len();
+                let mut image_urls: Vec<String> = Vec::new();
+                let mut chapters: Vec<Chapter> = Vec::with_capacity(total);
+                let mut scramble_id = None;
+                for (i, ep) in episodes.into_iter().enumerate() {
+                    progress.update(i, total, &format!("正在解析章节 {}", ep.title));
+                    let (_, urls, sid) = self.parse_photo(&request_ctx, &ep.url).await?;
+                    scramble_id = scramble_id.or(sid);
+                    chapters.push(Chapter { index: ep.index, title: ep.title, start: image_urls.len(), count: urls.len() });
+                    image_urls.extend(urls);
+                }
+                progress.update(total, total, "章节解析完成");
+                (title, image_urls, scramble_id, chapters)
+            } else {
+                // 单章节：专辑页只有封面，图片在对应的 /photo/ 页面
+                let photo_url = match episodes.first() {
+                    Some(ep) => ep.url.clone(),
+                    None => album_to_photo_url(url),
+                };
+                progress.update(0, 1, "正在解析图片链接");
+                let (photo_title, image_urls, scramble_id) = self.parse_photo(&request_ctx, &photo_url).await?;
+                progress.update(1, 1, "正在解析图片链接");
+                (title.or(photo_title), image_urls, scramble_id, Vec::new())
+            };
 
             progress.set_message("解析完成，准备下载");
 
-            Ok(ParsedGallery { title, image_urls, download_headers: None, recommended_concurrency: None })
+            // 新章节图片被切条打乱，下载后按页面脚本中的 scramble_id 还原
+            let scramble_id = scramble_id.unwrap_or(COMIC18_DEFAULT_SCRAMBLE_ID);
+            let image_transform = Some(ImageTransform::Comic18Descramble { scramble_id });
+
+            Ok(ParsedGallery { title, image_urls, download_headers: None, recommended_concurrency: None, metadata: Default::default(), image_transform, chapters })
+        })
+    }
+    fn list_chapters<'a>(&'a self, client: &'a Client, url: &'a str) -> core::pin::Pin<Box<dyn core::future::Future<Output = anyhow::Result<ChapterList>> + Send + 'a>> {
+        Box::pin(async move {
+            let request_ctx = RequestContext::with_concurrency(client.clone(), 1);
+            let (title, chapters) = self.fetch_album(&request_ctx, &photo_to_album_url(url)).await?;
+            Ok(ChapterList { title, chapters })
         })
     }
 }
 
+fn is_album_url(url: &str) -> bool {
+    url.contains("/album/")
+}
+
+/// `/album/<id>/...` -> `/photo/<id>`（单章节专辑的章节 id 与专辑 id 相同）
+fn album_to_photo_url(url: &str) -> String {
+    ALBUM_PATH_RE.replace(url, "/photo/$1").to_string()
+}
+
+/// 列出章节时统一使用专辑页面
+fn photo_to_album_url(url: &str) -> String {
+    PHOTO_PATH_RE.replace(url, "/album/$1").to_string()
+}
+
+fn parse_title(doc: &scraper::Html) -> Option<String> {
+    let sel = scraper::Selector::parse("h1").unwrap();
+    doc.select(&sel).next().map(|n| n.text().collect::<String>()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+fn parse_photo_images(doc: &scraper::Html) -> Vec<String> {
+    let sel_img = scraper::Selector::parse(".scramble-page > img").unwrap();
+    let mut image_urls: Vec<String> = doc
+        .select(&sel_img)
+        .filter_map(|img| img.value().attr("data-original").or_else(|| img.value().attr("src")))
+        .map(|src| src.to_string())
+        .collect();
+    image_urls.sort();
+    image_urls.dedup();
+    image_urls
+}
+
+/// 专辑页面的章节列表（`.episode` 中指向 /photo/<id> 的链接），按页面顺序编号
+fn parse_episodes(doc: &scraper::Html, page_url: &str) -> Vec<ChapterInfo> {
+    let sel = scraper::Selector::parse(".episode a[href*=\"/photo/\"]").unwrap();
+    let base = url::Url::parse(page_url).ok();
+    let mut chapters: Vec<ChapterInfo> = Vec::new();
+    for a in doc.select(&sel) {
+        let Some(href) = a.value().attr("href") else { continue };
+        let Some(id) = PHOTO_ID_RE.captures(href).and_then(|c| c.get(1)).map(|m| m.as_str().to_string()) else { continue };
+        if chapters.iter().any(|c| c.id == id) {
+            continue;
+        }
+        let url = base
+            .as_ref()
+            .and_then(|b| b.join(href).ok())
+            .map(|u| u.to_string())
+            .unwrap_or_else(|| href.to_string());
+        // 章节名后通常带有更新日期，只保留第一行
+        let text = a.text().collect::<String>();
+        let title = text
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty())
+            .map(|l| l.to_string())
+            .unwrap_or_else(|| format!("第{}話", chapters.len() + 1));
+        chapters.push(ChapterInfo { index: chapters.len() + 1, id, title, url, downloaded: false });
+    }
+    chapters
+}
+
+/// 页面脚本中的 `var scramble_id = 220980;`
+fn parse_scramble_id(html: &str) -> Option<u64> {
+    SCRAMBLE_ID_RE.captures(html)?.get(1)?.as_str().parse().ok()
+}
+
 pub fn register() {
     use crate::crawler::factory::{register, register_host_contains};
     register("18comic", || Box::new(Comic18Parser::new()));
     register_host_contains("18comic", vec!["18comic.vip", "18comic.org"]);
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_episode_list_in_page_order() {
+        let html = r#"
+            <div class="episode"><ul>
+                <a href="/photo/500001" data-album="500001"><li>第1話
+                    <span>2024-01-01</span></li></a>
+                <a href="/photo/500002"><li>第2話 <span>2024-01-08</span></li></a>
+                <a href="/photo/500001"><li>第1話</li></a>
+            </ul></div>
+            <a href="/photo/500001">開始閱讀</a>
+        "#;
+        let doc = scraper::Html::parse_document(html);
+
+        let chapters = parse_episodes(&doc, "https://18comic.vip/album/500001/title");
+
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].index, 1);
+        assert_eq!(chapters[0].title, "第1話");
+        assert_eq!(chapters[1].url, "https://18comic.vip/photo/500002");
+    }
+
+    #[test]
+    fn album_and_photo_urls_share_gallery_id() {
+        let parser = Comic18Parser::new();
 
+        assert_eq!(parser.gallery_id("https://18comic.vip/album/500001/x").as_deref(), Some("500001"));
+        assert_eq!(parser.gallery_id("https://18comic.vip/photo/500001").as_deref(), Some("500001"));
+        assert_eq!(album_to_photo_url("https://18comic.vip/album/500001/x?a=1"), "https://18comic.vip/photo/500001?a=1");
+    }
+}
@@ -1,5 +1,5 @@
 use crate::progress::ProgressContext;
-use crate::crawler::{ParsedGallery, ProgressReporter, SiteParser};
+use crate::crawler::{GalleryMetadata, ParsedGallery, ProgressReporter, SiteParser};
 use crate::request::Client;
 use crate::crawler::parsers::common::RequestContext;
 use crate::config::service::ConfigService;
@@ -7,6 +7,10 @@ use reqwest::header::{HeaderMap, COOKIE};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use url::Url;
+use once_cell::sync::Lazy;
+
+/// /g/<gid>/<token>/，同一画廊在表站和里站的 gid 相同
+static GALLERY_ID_RE: Lazy<regex::Regex> = Lazy::new(|| regex::Regex::new(r"/g/(\d+)/[0-9a-f]+").unwrap());
 
 
 
@@ -59,6 +63,56 @@ fn discover_gallery_page_urls(doc: &scraper::Html, url: &str) -> anyhow::Result<
     }
 }
 
+/// 从画廊首页提取元数据：日文标题、分类以及 #taglist 中按命名空间分组的标签
+fn parse_gallery_metadata(doc: &scraper::Html) -> GalleryMetadata {
+    let mut metadata = GalleryMetadata::default();
+    let text_of = |selector: &str| {
+        scraper::Selector::parse(selector).ok().and_then(|sel| {
+            doc.select(&sel)
+                .next()
+                .map(|n| n.text().collect::<String>().trim().to_string())
+                .filter(|s| !s.is_empty())
+        })
+    };
+    metadata.original_title = text_of("#gj");
+    metadata.category = text_of("#gdc .cs").or_else(|| text_of("#gdc div"));
+
+    let (Ok(sel_row), Ok(sel_ns), Ok(sel_tag)) = (
+        scraper::Selector::parse("#taglist tr"),
+        scraper::Selector::parse("td.tc"),
+        scraper::Selector::parse("td div a"),
+    ) else {
+        return metadata;
+    };
+    for row in doc.select(&sel_row) {
+        let namespace = row
+            .select(&sel_ns)
+            .next()
+            .map(|n| n.text().collect::<String>().trim().trim_end_matches(':').to_string())
+            .unwrap_or_default();
+        let values: Vec<String> = row
+            .select(&sel_tag)
+            .map(|a| a.text().collect::<String>().trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        match namespace.as_str() {
+            "artist" => metadata.artists.extend(values),
+            "group" => metadata.groups.extend(values),
+            "parody" => metadata.parodies.extend(values),
+            "character" => metadata.characters.extend(values),
+            "language" => {
+                // language 命名空间里还会出现 translated / rewrite 等标记
+                metadata.language = values
+                    .into_iter()
+                    .find(|v| v != "translated" && v != "rewrite");
+            }
+            "" => metadata.tags.extend(values),
+            ns => metadata.tags.extend(values.into_iter().map(|v| format!("{}:{}", ns, v))),
+        }
+    }
+    metadata
+}
+
 impl EhentaiParser {
     pub fn new() -> Self {
         Self
@@ -70,7 +124,7 @@ impl EhentaiParser {
         request_ctx: &RequestContext,
         url: &str,
         progress: &ProgressContext,
-    ) -> anyhow::Result<(Option<String>, Vec<String>)> {
+    ) -> anyhow::Result<(Option<String>, Vec<String>, GalleryMetadata)> {
         progress.set_message("正在获取专辑信息");
 
         let html = request_ctx.fetch_html(url).await?;
@@ -85,6 +139,8 @@ impl EhentaiParser {
                 .filter(|s| !s.trim().is_empty())
         };
 
+        let metadata = parse_gallery_metadata(&doc);
+
         // 提取页面URLs
         let mut page_urls = discover_gallery_page_urls(&doc, url)?;
 
@@ -94,7 +150,7 @@ impl EhentaiParser {
             page_urls.retain(|u| seen.insert(u.clone()));
         }
 
-        Ok((title, page_urls))
+        Ok((title, page_urls, metadata))
     }
 
     async fn extract_thumbnail_urls(
@@ -275,6 +331,9 @@ impl SiteParser for EhentaiParser {
     fn domains(&self) -> &'static [&'static str] {
         &["e-hentai.org", "exhentai.org"]
     }
+    fn gallery_id(&self, url: &str) -> Option<String> {
+        Some(GALLERY_ID_RE.captures(url)?.get(1)?.as_str().to_string())
+    }
     fn parse<'a>(
         &'a self,
         client: &'a Client,
@@ -300,7 +359,7 @@ impl SiteParser for EhentaiParser {
             let progress = ProgressContext::new(reporter, "EHentai".to_string());
 
             // 1. 发现所有页面
-            let (title, page_urls) = self.discover_pages(&request_ctx, url, &progress).await?;
+            let (title, page_urls, metadata) = self.discover_pages(&request_ctx, url, &progress).await?;
 
             // 2. 提取缩略图
             let thumbnail_urls = self.extract_thumbnail_urls(request_ctx.clone(), page_urls, progress.clone()).await?;
@@ -320,6 +379,9 @@ impl SiteParser for EhentaiParser {
                 image_urls,
                 download_headers: None,
                 recommended_concurrency: None,
+                metadata,
+                image_transform: None,
+                chapters: Vec::new(),
             })
         })
     }
@@ -365,6 +427,36 @@ mod tests {
         assert_eq!(page_urls.last().unwrap(), "https://e-hentai.org/g/123/abc/?p=42");
     }
 
+    #[test]
+    fn parses_namespaced_tags_into_metadata_fields() {
+        let html = r#"
+            <html>
+                <body>
+                    <h1 id="gj">原題</h1>
+                    <div id="gdc"><div class="cs ct3">Doujinshi</div></div>
+                    <div id="taglist">
+                        <table>
+                            <tr><td class="tc">language:</td><td><div><a>chinese</a></div><div><a>translated</a></div></td></tr>
+                            <tr><td class="tc">parody:</td><td><div><a>original</a></div></td></tr>
+                            <tr><td class="tc">artist:</td><td><div><a>some artist</a></div></td></tr>
+                            <tr><td class="tc">female:</td><td><div><a>glasses</a></div></td></tr>
+                        </table>
+                    </div>
+                </body>
+            </html>
+        "#;
+        let doc = scraper::Html::parse_document(html);
+
+        let metadata = parse_gallery_metadata(&doc);
+
+        assert_eq!(metadata.original_title.as_deref(), Some("原題"));
+        assert_eq!(metadata.category.as_deref(), Some("Doujinshi"));
+        assert_eq!(metadata.language.as_deref(), Some("chinese"));
+        assert_eq!(metadata.parodies, vec!["original".to_string()]);
+        assert_eq!(metadata.artists, vec!["some artist".to_string()]);
+        assert_eq!(metadata.tags, vec!["female:glasses".to_string()]);
+    }
+
     #[test]
     fn falls_back_to_current_url_when_no_page_index_links_exist() {
         let html = r#"
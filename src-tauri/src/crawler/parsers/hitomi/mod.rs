@@ -1,4 +1,5 @@
 pub mod gg_parser;
+pub mod nozomi;
 pub mod parser;
 pub mod types;
 pub mod utils;
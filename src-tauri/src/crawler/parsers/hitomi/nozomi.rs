@@ -0,0 +1,101 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// nozomi 文件所在域名
+pub const NOZOMI_DOMAIN: &str = "ltn.gold-usergeneratedcontent.net";
+
+/// 与站点列表页一致，每页 25 个画廊
+pub const GALLERIES_PER_PAGE: usize = 25;
+
+static AREA_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^/(tag|artist|series|group|character|type)/([^/]+)-([a-z]+)\.html$").unwrap());
+static INDEX_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^/index-([a-z]+)\.html$").unwrap());
+
+/// 列表页对应的 nozomi 文件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NozomiListing {
+    pub url: String,
+    /// 列表页的 `?page=` 参数（从 1 开始），为空表示整个列表
+    pub page: Option<usize>,
+}
+
+/// 将列表页地址转换为 nozomi 文件地址，例如
+/// `https://hitomi.la/tag/female%3Abig%20breasts-all.html?page=2`
+/// -> `https://ltn.gold-usergeneratedcontent.net/n/tag/female%3Abig%20breasts-all.nozomi`（第 2 页）
+///
+/// 支持 tag、artist、series、group、character、type 列表以及 `index-<language>.html` 语言首页
+pub fn listing_from_url(url: &str) -> Option<NozomiListing> {
+    let parsed = url::Url::parse(url).ok()?;
+    let page = parsed
+        .query_pairs()
+        .find(|(k, _)| k == "page")
+        .and_then(|(_, v)| v.parse::<usize>().ok())
+        .filter(|p| *p > 0);
+
+    let path = parsed.path();
+    let name = if let Some(caps) = AREA_RE.captures(path) {
+        format!("{}/{}-{}", &caps[1], &caps[2], &caps[3])
+    } else if let Some(caps) = INDEX_RE.captures(path) {
+        format!("index-{}", &caps[1])
+    } else if path == "/" || path.is_empty() {
+        "index-all".to_string()
+    } else {
+        return None;
+    };
+    Some(NozomiListing { url: format!("https://{}/n/{}.nozomi", NOZOMI_DOMAIN, name), page })
+}
+
+/// 指定页对应的字节范围（每个画廊 ID 占 4 字节），用于 Range 请求
+pub fn page_byte_range(page: usize) -> (u64, u64) {
+    let per_page = (GALLERIES_PER_PAGE * 4) as u64;
+    let start = (page.max(1) as u64 - 1) * per_page;
+    (start, start + per_page - 1)
+}
+
+/// nozomi 文件内容是大端序 i32 画廊 ID 数组
+pub fn parse_nozomi(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .chunks_exact(4)
+        .map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]]))
+        .collect()
+}
+
+/// 画廊页面地址，`utils::extract_id` 可以从中取回 ID
+pub fn gallery_url(id: u32) -> String {
+    format!("https://hitomi.la/galleries/{}.html", id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_listing_urls_to_nozomi_files() {
+        assert_eq!(
+            listing_from_url("https://hitomi.la/tag/female%3Abig%20breasts-all.html?page=2"),
+            Some(NozomiListing {
+                url: "https://ltn.gold-usergeneratedcontent.net/n/tag/female%3Abig%20breasts-all.nozomi".to_string(),
+                page: Some(2),
+            })
+        );
+        assert_eq!(
+            listing_from_url("https://hitomi.la/artist/foo-japanese.html").map(|l| l.url),
+            Some("https://ltn.gold-usergeneratedcontent.net/n/artist/foo-japanese.nozomi".to_string())
+        );
+        assert_eq!(
+            listing_from_url("https://hitomi.la/index-korean.html").map(|l| l.url),
+            Some("https://ltn.gold-usergeneratedcontent.net/n/index-korean.nozomi".to_string())
+        );
+        assert_eq!(listing_from_url("https://hitomi.la/doujinshi/title-japanese-123.html"), None);
+    }
+
+    #[test]
+    fn decodes_big_endian_ids_and_page_ranges() {
+        let bytes = [0x00, 0x1e, 0x84, 0x80, 0x00, 0x00, 0x00, 0x07, 0xff];
+
+        assert_eq!(parse_nozomi(&bytes), vec![2_000_000, 7]);
+        assert_eq!(page_byte_range(1), (0, 99));
+        assert_eq!(page_byte_range(3), (200, 299));
+        assert_eq!(crate::crawler::parsers::hitomi::utils::extract_id(&gallery_url(7)).as_deref(), Some("7"));
+    }
+}
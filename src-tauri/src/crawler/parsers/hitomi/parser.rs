@@ -27,6 +27,10 @@ impl SiteParser for HitomiParser {
         &["hitomi.la"]
     }
 
+    fn gallery_id(&self, url: &str) -> Option<String> {
+        extract_id(url)
+    }
+
     fn parse<'a>(
         &'a self,
         client: &'a Client,
@@ -57,7 +61,9 @@ impl SiteParser for HitomiParser {
                 id
             );
             let gi_text = request_ctx.fetch_html(&gi_url).await?;
-            let (title, files) = parse_galleryinfo(&gi_text)?;
+            let gallery_info = parse_galleryinfo(&gi_text)?;
+            let metadata = gallery_info.metadata();
+            let (title, files) = (gallery_info.title, gallery_info.files);
 
             if files.is_empty() {
                 anyhow::bail!("未找到任何文件信息");
@@ -111,6 +117,9 @@ impl SiteParser for HitomiParser {
                     Some(h)
                 },
                 recommended_concurrency: Some(4),
+                metadata,
+                image_transform: None,
+                chapters: Vec::new(),
             })
         })
     }
@@ -1,9 +1,93 @@
 use serde::Deserialize;
 
+use crate::crawler::GalleryMetadata;
+
 #[derive(Deserialize)]
 pub struct GalleryInfo {
     pub title: String,
     pub files: Vec<HitomiFile>,
+    #[serde(default)]
+    pub japanese_title: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default, rename = "type")]
+    pub gallery_type: Option<String>,
+    // 以下列表字段在 galleryinfo 中可能为 null
+    #[serde(default)]
+    pub artists: Option<Vec<NamedEntry>>,
+    #[serde(default)]
+    pub groups: Option<Vec<NamedEntry>>,
+    #[serde(default)]
+    pub parodys: Option<Vec<NamedEntry>>,
+    #[serde(default)]
+    pub characters: Option<Vec<NamedEntry>>,
+    #[serde(default)]
+    pub tags: Option<Vec<HitomiTag>>,
+}
+
+/// artists / groups / parodys / characters 的条目，名称字段随列表类型变化
+#[derive(Deserialize)]
+pub struct NamedEntry {
+    #[serde(alias = "artist", alias = "group", alias = "parody", alias = "character")]
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+pub struct HitomiTag {
+    pub tag: String,
+    // "1" / 1 / "" 均可能出现
+    #[serde(default)]
+    pub female: Option<serde_json::Value>,
+    #[serde(default)]
+    pub male: Option<serde_json::Value>,
+}
+
+fn is_flag_set(v: &Option<serde_json::Value>) -> bool {
+    match v {
+        Some(serde_json::Value::String(s)) => s == "1",
+        Some(serde_json::Value::Number(n)) => n.as_i64() == Some(1),
+        Some(serde_json::Value::Bool(b)) => *b,
+        _ => false,
+    }
+}
+
+impl GalleryInfo {
+    pub fn metadata(&self) -> GalleryMetadata {
+        let names = |list: &Option<Vec<NamedEntry>>| {
+            list.as_ref()
+                .map(|v| v.iter().map(|e| e.name.clone()).collect())
+                .unwrap_or_default()
+        };
+        let tags = self
+            .tags
+            .as_ref()
+            .map(|v| {
+                v.iter()
+                    .map(|t| {
+                        if is_flag_set(&t.female) {
+                            format!("female:{}", t.tag)
+                        } else if is_flag_set(&t.male) {
+                            format!("male:{}", t.tag)
+                        } else {
+                            t.tag.clone()
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        GalleryMetadata {
+            original_title: self.japanese_title.clone(),
+            artists: names(&self.artists),
+            groups: names(&self.groups),
+            parodies: names(&self.parodys),
+            characters: names(&self.characters),
+            tags,
+            language: self.language.clone(),
+            category: self.gallery_type.clone(),
+            page_count: Some(self.files.len()),
+            ..GalleryMetadata::default()
+        }
+    }
 }
 
 #[derive(Deserialize)]
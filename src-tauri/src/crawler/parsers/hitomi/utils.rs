@@ -1,15 +1,15 @@
-use crate::crawler::parsers::hitomi::types::{GalleryInfo, HitomiFile};
+use crate::crawler::parsers::hitomi::types::GalleryInfo;
 use regex::Regex;
 
-/// 从URL中提取ID
+/// 从URL中提取ID，支持 `.../title-japanese-123.html` 与 `/galleries/123.html`、`/reader/123.html`
 pub fn extract_id(url: &str) -> Option<String> {
-    let re = Regex::new(r"-(\d+)\.html").ok()?;
+    let re = Regex::new(r"(?:-|/galleries/|/reader/)(\d+)\.html").ok()?;
     let caps = re.captures(url)?;
     Some(caps.get(1)?.as_str().to_string())
 }
 
 /// 解析galleryinfo JavaScript
-pub fn parse_galleryinfo(js_text: &str) -> anyhow::Result<(String, Vec<HitomiFile>)> {
+pub fn parse_galleryinfo(js_text: &str) -> anyhow::Result<GalleryInfo> {
     // 参考Go版本的正则表达式：var galleryinfo = (.+);?
     let re = Regex::new(r"var galleryinfo = (.+);?")?;
     let caps = re
@@ -23,6 +23,6 @@ pub fn parse_galleryinfo(js_text: &str) -> anyhow::Result<(String, Vec<HitomiFil
             return Err(anyhow::anyhow!("JSON 解析失败: {}", e));
         }
     };
-    Ok((gi.title, gi.files))
+    Ok(gi)
 }
 
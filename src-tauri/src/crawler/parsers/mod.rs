@@ -6,6 +6,7 @@ pub mod hitomi;
 pub mod wnacg;   // Wnacg 解析器
 pub mod comic18; // 18comic 解析器
 pub mod pixiv;   // Pixiv 解析器
+pub mod rule;    // 配置文件定义的规则解析器
 
 pub fn register_all() {
     // 分模块注册站点解析器与 host 匹配器
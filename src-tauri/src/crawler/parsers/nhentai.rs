@@ -1,4 +1,4 @@
-use crate::crawler::{ParsedGallery, ProgressReporter, SiteParser};
+use crate::crawler::{GalleryMetadata, ParsedGallery, ProgressReporter, SiteParser};
 use crate::progress::ProgressContext;
 use crate::request::Client;
 use crate::config::service::ConfigService;
@@ -31,6 +31,9 @@ impl SiteParser for NhentaiParser {
     fn domains(&self) -> &'static [&'static str] {
         &["nhentai.net", "nhentai.xxx", "nhentai.to"]
     }
+    fn gallery_id(&self, url: &str) -> Option<String> {
+        extract_gallery_id(url).ok()
+    }
     fn parse<'a>(
         &'a self,
         client: &'a Client,
@@ -64,7 +67,7 @@ impl SiteParser for NhentaiParser {
             let html: String = resp.text().await?;
 
             // 解析HTML并提取数据
-            let (title, thumbs, api_params) = parse_html_content(&html);
+            let (title, thumbs, api_params, metadata) = parse_html_content(&html);
 
             if thumbs.is_empty() {
                 anyhow::bail!("未找到任何图片");
@@ -119,6 +122,9 @@ impl SiteParser for NhentaiParser {
                 image_urls,
                 download_headers: None,
                 recommended_concurrency: None,
+                metadata,
+                image_transform: None,
+                chapters: Vec::new(),
             })
         })
     }
@@ -200,7 +206,7 @@ struct ApiParams {
 }
 
 /// 解析HTML内容并提取所需数据
-fn parse_html_content(html: &str) -> (Option<String>, Vec<String>, Option<ApiParams>) {
+fn parse_html_content(html: &str) -> (Option<String>, Vec<String>, Option<ApiParams>, GalleryMetadata) {
     let doc = scraper::Html::parse_document(html);
     
     // 提取标题
@@ -226,8 +232,58 @@ fn parse_html_content(html: &str) -> (Option<String>, Vec<String>, Option<ApiPar
     
     // 提取API参数
     let api_params = extract_api_params(&doc);
-    
-    (title, thumbs, api_params)
+
+    let metadata = extract_gallery_metadata(&doc);
+
+    (title, thumbs, api_params, metadata)
+}
+
+/// 从标签链接中提取元数据
+/// 标签链接的路径前缀（/artist/、/tag/ 等）即为分类，同时兼容 nhentai.net 与镜像站的页面结构
+fn extract_gallery_metadata(doc: &scraper::Html) -> GalleryMetadata {
+    let mut metadata = GalleryMetadata::default();
+    let sel_link = scraper::Selector::parse("a[href]").unwrap();
+    let sel_name = scraper::Selector::parse(".tag_name, .name").unwrap();
+    for a in doc.select(&sel_link) {
+        let href = a.value().attr("href").unwrap_or("");
+        let path = href
+            .split("://")
+            .nth(1)
+            .and_then(|rest| rest.find('/').map(|i| &rest[i..]))
+            .unwrap_or(href);
+        let Some(kind) = path.trim_start_matches('/').split('/').next() else { continue; };
+        let name = a
+            .select(&sel_name)
+            .next()
+            .map(|n| n.text().collect::<String>())
+            .unwrap_or_else(|| a.text().collect::<String>());
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            continue;
+        }
+        let target = match kind {
+            "artist" => &mut metadata.artists,
+            "group" => &mut metadata.groups,
+            "parody" => &mut metadata.parodies,
+            "character" => &mut metadata.characters,
+            "tag" => &mut metadata.tags,
+            "language" => {
+                if name != "translated" && metadata.language.is_none() {
+                    metadata.language = Some(name);
+                }
+                continue;
+            }
+            "category" => {
+                metadata.category.get_or_insert(name);
+                continue;
+            }
+            _ => continue,
+        };
+        if !target.contains(&name) {
+            target.push(name);
+        }
+    }
+    metadata
 }
 
 /// 从HTML中提取API参数
@@ -355,3 +411,30 @@ pub fn register() {
     register("nhentai", || Box::new(NhentaiParser::new()));
     register_host_contains("nhentai", vec!["nhentai.net", "nhentai.xxx", "nhentai.to"]);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_tag_links_by_path_prefix() {
+        let html = r#"
+            <div class="info">
+                <a class="tag_btn" href="/artist/foo/"><span class="tag_name">foo</span><span class="tag_count">12</span></a>
+                <a class="tag" href="https://nhentai.net/tag/glasses/"><span class="name">glasses</span><span class="count">3k</span></a>
+                <a href="/language/translated/"><span class="tag_name">translated</span></a>
+                <a href="/language/english/"><span class="tag_name">english</span></a>
+                <a href="/category/doujinshi/"><span class="tag_name">doujinshi</span></a>
+                <a href="/g/123/">gallery</a>
+            </div>
+        "#;
+        let doc = scraper::Html::parse_document(html);
+
+        let metadata = extract_gallery_metadata(&doc);
+
+        assert_eq!(metadata.artists, vec!["foo".to_string()]);
+        assert_eq!(metadata.tags, vec!["glasses".to_string()]);
+        assert_eq!(metadata.language.as_deref(), Some("english"));
+        assert_eq!(metadata.category.as_deref(), Some("doujinshi"));
+    }
+}
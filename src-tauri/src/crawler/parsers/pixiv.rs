@@ -3,6 +3,8 @@ use crate::request::Client;
 use crate::progress::ProgressContext;
 use crate::crawler::parsers::common::RequestContext;
 use crate::config::service::ConfigService;
+use crate::config::parser_config::ParserConfig;
+use crate::download::transform::{ImageTransform, UgoiraFormat, UgoiraFrame};
 use reqwest::header::{HeaderMap, HeaderValue, COOKIE};
 use serde_json::Value;
 use scraper::{Html, Selector};
@@ -57,6 +59,10 @@ impl SiteParser for PixivParser {
         &["pixiv.net"]
     }
 
+    fn gallery_id(&self, url: &str) -> Option<String> {
+        self.extract_artwork_id(url).ok()
+    }
+
     fn parse<'a>(
         &'a self,
         client: &'a Client,
@@ -77,6 +83,8 @@ impl SiteParser for PixivParser {
             // 从配置中获取 parser 配置
             let parser_config = app_state.map(|state| state.config.read().get_parser_config("pixiv"));
 
+            let settings = PixivSettings::from_config(parser_config.as_ref());
+
             let cookies = parser_config
                 .and_then(|config| config.auth)
                 .and_then(|auth| auth.cookies)
@@ -107,33 +115,25 @@ impl SiteParser for PixivParser {
 
             progress.update(25, 100, "正在获取图片列表");
 
-            // 构造 AJAX API URL
-            let ajax_url = format!("https://www.pixiv.net/ajax/illust/{}/pages?lang=zh", artwork_id);
-
-            // 获取图片数据
-            let resp = request_ctx.client
-                .get_with_headers_rate_limited(&ajax_url, &request_ctx.headers)
-                .await?;
-
-            if !resp.status().is_success() {
-                anyhow::bail!("获取图片数据失败，状态码: {}", resp.status());
-            }
-
-            let json_text = resp.text().await?;
-            let json_value: Value = serde_json::from_str(&json_text)
-                .context("解析 JSON 响应失败")?;
-
-            // 解析图片 URL
-            let mut image_urls = Vec::new();
-            if let Some(body) = json_value.get("body").and_then(|b| b.as_array()) {
-                for page in body {
-                    if let Some(urls) = page.get("urls") {
-                        if let Some(regular_url) = urls.get("regular").and_then(|u| u.as_str()) {
-                            image_urls.push(regular_url.to_string());
-                        }
-                    }
-                }
-            }
+            // 动图（illustType == 2）的图片列表在 ugoira_meta 接口中
+            let detail = fetch_json(&request_ctx, &format!("https://www.pixiv.net/ajax/illust/{}?lang=zh", artwork_id)).await?;
+            let is_ugoira = detail
+                .pointer("/body/illustType")
+                .and_then(|v| v.as_i64())
+                == Some(2);
+
+            let (image_urls, image_transform) = if is_ugoira {
+                let meta_url = format!("https://www.pixiv.net/ajax/illust/{}/ugoira_meta?lang=zh", artwork_id);
+                let meta = fetch_json(&request_ctx, &meta_url).await?;
+                let (zip_url, frames) = parse_ugoira_meta(&meta, settings.original)
+                    .ok_or_else(|| anyhow::anyhow!("解析动图信息失败"))?;
+                let transform = ImageTransform::PixivUgoira { frames, format: settings.ugoira_format };
+                (vec![zip_url], Some(transform))
+            } else {
+                let pages_url = format!("https://www.pixiv.net/ajax/illust/{}/pages?lang=zh", artwork_id);
+                let pages = fetch_json(&request_ctx, &pages_url).await?;
+                (parse_page_urls(&pages, settings.original), None)
+            };
 
             if image_urls.is_empty() {
                 anyhow::bail!("未找到任何图片");
@@ -155,13 +155,144 @@ impl SiteParser for PixivParser {
                 image_urls,
                 download_headers: Some(download_headers),
                 recommended_concurrency,
+                metadata: Default::default(),
+                image_transform,
+                chapters: Vec::new(),
             })
         })
     }
 }
 
+/// Pixiv 站点设置，来自 `site_specific.settings`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct PixivSettings {
+    /// 下载原图（`urls.original`），否则下载缩小后的 `urls.regular`
+    original: bool,
+    ugoira_format: UgoiraFormat,
+}
+
+impl PixivSettings {
+    fn from_config(config: Option<&ParserConfig>) -> Self {
+        let settings = config
+            .and_then(|c| c.site_specific.as_ref())
+            .map(|s| &s.settings);
+        let Some(settings) = settings else { return Self::default(); };
+        Self {
+            original: settings.get("original").and_then(|v| v.as_bool()).unwrap_or(false),
+            ugoira_format: settings
+                .get("ugoira_format")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+async fn fetch_json(request_ctx: &RequestContext, url: &str) -> anyhow::Result<Value> {
+    let resp = request_ctx.client
+        .get_with_headers_rate_limited(url, &request_ctx.headers)
+        .await?;
+
+    if !resp.status().is_success() {
+        anyhow::bail!("获取图片数据失败，状态码: {}", resp.status());
+    }
+
+    let json_text = resp.text().await?;
+    let json_value: Value = serde_json::from_str(&json_text)
+        .context("解析 JSON 响应失败")?;
+    if json_value.get("error").and_then(|e| e.as_bool()) == Some(true) {
+        let message = json_value.get("message").and_then(|m| m.as_str()).unwrap_or_default();
+        anyhow::bail!("Pixiv 接口返回错误: {}", message);
+    }
+    Ok(json_value)
+}
+
+/// 从 `/ajax/illust/<id>/pages` 响应中取出每页的图片地址
+fn parse_page_urls(json: &Value, original: bool) -> Vec<String> {
+    let key = if original { "original" } else { "regular" };
+    json.get("body")
+        .and_then(|b| b.as_array())
+        .map(|pages| {
+            pages
+                .iter()
+                .filter_map(|page| page.get("urls")?.get(key)?.as_str())
+                .map(|u| u.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 从 `/ajax/illust/<id>/ugoira_meta` 响应中取出帧压缩包地址与帧列表
+fn parse_ugoira_meta(json: &Value, original: bool) -> Option<(String, Vec<UgoiraFrame>)> {
+    let body = json.get("body")?;
+    let src = if original {
+        body.get("originalSrc").or_else(|| body.get("src"))
+    } else {
+        body.get("src")
+    };
+    let frames: Vec<UgoiraFrame> = serde_json::from_value(body.get("frames")?.clone()).ok()?;
+    if frames.is_empty() {
+        return None;
+    }
+    Some((src?.as_str()?.to_string(), frames))
+}
+
 pub fn register() {
     use crate::crawler::factory::{register, register_host_contains};
     register("pixiv", || Box::new(PixivParser::new()));
     register_host_contains("pixiv", vec!["pixiv.net"]);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_regular_or_original_page_urls() {
+        let json = serde_json::json!({
+            "error": false,
+            "body": [
+                { "urls": { "regular": "https://i.pximg.net/img-master/p0_master1200.jpg", "original": "https://i.pximg.net/img-original/p0.png" } },
+                { "urls": { "regular": "https://i.pximg.net/img-master/p1_master1200.jpg", "original": "https://i.pximg.net/img-original/p1.png" } }
+            ]
+        });
+
+        assert_eq!(parse_page_urls(&json, false)[1], "https://i.pximg.net/img-master/p1_master1200.jpg");
+        assert_eq!(parse_page_urls(&json, true), vec![
+            "https://i.pximg.net/img-original/p0.png".to_string(),
+            "https://i.pximg.net/img-original/p1.png".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn parses_ugoira_meta_frames() {
+        let json = serde_json::json!({
+            "error": false,
+            "body": {
+                "src": "https://i.pximg.net/img-zip-ugoira/1_ugoira600x600.zip",
+                "originalSrc": "https://i.pximg.net/img-zip-ugoira/1_ugoira1920x1080.zip",
+                "mime_type": "image/jpeg",
+                "frames": [{ "file": "000000.jpg", "delay": 100 }, { "file": "000001.jpg", "delay": 60 }]
+            }
+        });
+
+        let (url, frames) = parse_ugoira_meta(&json, true).unwrap();
+
+        assert_eq!(url, "https://i.pximg.net/img-zip-ugoira/1_ugoira1920x1080.zip");
+        assert_eq!(frames[1], UgoiraFrame { file: "000001.jpg".to_string(), delay: 60 });
+    }
+
+    #[test]
+    fn reads_settings_from_site_specific_config() {
+        let mut config = ParserConfig::default();
+        let mut site = crate::config::parser_config::SiteSpecificConfig::default();
+        site.settings.insert("original".to_string(), serde_json::json!(true));
+        site.settings.insert("ugoira_format".to_string(), serde_json::json!("zip"));
+        config.site_specific = Some(site);
+
+        let settings = PixivSettings::from_config(Some(&config));
+
+        assert!(settings.original);
+        assert_eq!(settings.ugoira_format, UgoiraFormat::Zip);
+        assert_eq!(PixivSettings::from_config(None), PixivSettings::default());
+    }
+}
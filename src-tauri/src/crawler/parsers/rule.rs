@@ -0,0 +1,368 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use reqwest::header::{HeaderMap, HeaderValue, REFERER};
+use serde::{Deserialize, Serialize};
+
+use crate::crawler::parsers::common::RequestContext;
+use crate::crawler::{ParsedGallery, ProgressReporter, SiteParser};
+use crate::progress::ProgressContext;
+use crate::request::Client;
+
+/// 规则文件所在目录（位于配置目录下）
+pub const RULES_DIR: &str = "parsers";
+
+/// 规则解析器的站点类型前缀，避免与内置解析器重名
+const SITE_TYPE_PREFIX: &str = "custom:";
+
+/// 跟随分页时最多读取的页数
+const DEFAULT_MAX_PAGES: usize = 50;
+
+/// 声明式站点规则，从配置目录中的 JSON / TOML 文件加载
+///
+/// ```toml
+/// name = "example"
+/// hosts = ["example.com"]
+/// title_selector = "h1"
+/// image_selector = "#gallery img"
+/// image_attr = "data-src"
+/// next_page_selector = "a.next"
+/// referer = "https://example.com/"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SiteRule {
+    pub name: String,
+    /// host 为其中任一域名或其子域名时使用该规则；内置站点的域名始终由内置解析器处理
+    pub hosts: Vec<String>,
+    pub title_selector: Option<String>,
+    pub image_selector: String,
+    /// 图片地址所在属性，依次尝试，默认 `src`
+    #[serde(default)]
+    pub image_attr: Vec<String>,
+    /// 下一页链接，为空表示单页画廊
+    pub next_page_selector: Option<String>,
+    pub max_pages: Option<usize>,
+    /// 下载图片时附带的 Referer
+    pub referer: Option<String>,
+    /// 从 URL 中提取画廊 ID 的正则（第一个捕获组），用于去重
+    pub gallery_id_pattern: Option<String>,
+    pub concurrency: Option<usize>,
+}
+
+impl SiteRule {
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.name.trim().is_empty() {
+            anyhow::bail!("规则缺少 name");
+        }
+        if self.hosts.iter().all(|h| h.trim().is_empty()) {
+            anyhow::bail!("规则 {} 缺少 hosts", self.name);
+        }
+        let selectors = [Some(&self.image_selector), self.title_selector.as_ref(), self.next_page_selector.as_ref()];
+        for selector in selectors.into_iter().flatten() {
+            scraper::Selector::parse(selector)
+                .map_err(|e| anyhow::anyhow!("规则 {} 的选择器 `{}` 无效: {}", self.name, selector, e))?;
+        }
+        if let Some(pattern) = &self.gallery_id_pattern {
+            regex::Regex::new(pattern)?;
+        }
+        Ok(())
+    }
+
+    fn site_type(&self) -> String {
+        format!("{}{}", SITE_TYPE_PREFIX, self.name.trim())
+    }
+}
+
+/// 从文件读取规则，按扩展名选择 JSON 或 TOML
+pub fn load_rule_file(path: &Path) -> anyhow::Result<SiteRule> {
+    let text = std::fs::read_to_string(path)?;
+    let rule: SiteRule = match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+        Some("toml") => toml::from_str(&text)?,
+        _ => serde_json::from_str(&text)?,
+    };
+    rule.validate()?;
+    Ok(rule)
+}
+
+/// 一次加载的结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleLoadReport {
+    pub loaded: Vec<String>,
+    /// (文件, 错误信息)
+    pub errors: Vec<(String, String)>,
+}
+
+// 当前已注册的规则站点类型，重新加载时先移除
+static REGISTERED: Lazy<RwLock<Vec<&'static str>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// 扫描配置目录下的规则文件并注册为站点解析器，替换上一次加载的规则
+pub fn load_rules_from_dir(config_dir: &Path) -> RuleLoadReport {
+    let mut report = RuleLoadReport::default();
+    let dir = config_dir.join(RULES_DIR);
+
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| {
+                    p.is_file()
+                        && matches!(
+                            p.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref(),
+                            Some("json") | Some("toml")
+                        )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+
+    let mut rules = Vec::new();
+    for path in files {
+        match load_rule_file(&path) {
+            Ok(rule) if rules.iter().any(|r: &SiteRule| r.site_type() == rule.site_type()) => {
+                report.errors.push((path.display().to_string(), format!("规则名称重复: {}", rule.name)));
+            }
+            Ok(rule) => rules.push(rule),
+            Err(e) => report.errors.push((path.display().to_string(), e.to_string())),
+        }
+    }
+
+    // 先注册内置解析器，规则的 host 匹配器排在其后，与加载时机无关
+    crate::crawler::ensure_builtin_registered();
+    for site_type in REGISTERED.write().drain(..) {
+        crate::crawler::factory::unregister(site_type);
+    }
+    for rule in rules {
+        report.loaded.push(rule.name.clone());
+        register_rule(rule);
+    }
+    report
+}
+
+fn register_rule(rule: SiteRule) {
+    use crate::crawler::factory::{register, register_host_matcher};
+
+    // 站点类型需要 'static 生命周期，规则数量有限，直接泄漏
+    let site_type: &'static str = Box::leak(rule.site_type().into_boxed_str());
+    let hosts: Vec<String> = rule
+        .hosts
+        .iter()
+        .map(|h| h.trim().to_ascii_lowercase())
+        .filter(|h| !h.is_empty())
+        .collect();
+    // 加载时已校验过正则
+    let gallery_id_re = rule.gallery_id_pattern.as_deref().and_then(|p| regex::Regex::new(p).ok());
+    let rule = Arc::new(rule);
+
+    register(site_type, move || {
+        Box::new(RuleParser { site_type, rule: rule.clone(), gallery_id_re: gallery_id_re.clone() })
+    });
+    register_host_matcher(site_type, Box::new(move |host: &str| {
+        let host = host.to_ascii_lowercase();
+        hosts.iter().any(|h| host_matches(&host, h))
+    }));
+    REGISTERED.write().push(site_type);
+}
+
+/// host 等于规则域名或为其子域名，`example.com` 不匹配 `notexample.com`
+fn host_matches(host: &str, domain: &str) -> bool {
+    host == domain || host.strip_suffix(domain).is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+/// 按 `SiteRule` 解析画廊
+pub struct RuleParser {
+    site_type: &'static str,
+    rule: Arc<SiteRule>,
+    gallery_id_re: Option<regex::Regex>,
+}
+
+impl SiteParser for RuleParser {
+    fn name(&self) -> &'static str {
+        self.site_type
+    }
+
+    fn gallery_id(&self, url: &str) -> Option<String> {
+        Some(self.gallery_id_re.as_ref()?.captures(url)?.get(1)?.as_str().to_string())
+    }
+
+    fn parse<'a>(
+        &'a self,
+        client: &'a Client,
+        url: &'a str,
+        reporter: Option<Arc<dyn ProgressReporter>>,
+        _app_state: Option<&'a crate::AppState>,
+    ) -> core::pin::Pin<
+        Box<dyn core::future::Future<Output = anyhow::Result<ParsedGallery>> + Send + 'a>,
+    > {
+        Box::pin(async move {
+            let rule = self.rule.clone();
+            let progress = ProgressContext::new(reporter, rule.name.clone());
+
+            let mut headers = HeaderMap::new();
+            if let Some(referer) = rule.referer.as_deref() {
+                headers.insert(REFERER, HeaderValue::from_str(referer)?);
+            }
+            let request_ctx = RequestContext::new(client.clone(), headers.clone(), rule.concurrency.unwrap_or(3));
+
+            let max_pages = rule.max_pages.unwrap_or(DEFAULT_MAX_PAGES).max(1);
+            let mut visited = HashSet::new();
+            let mut title = None;
+            let mut image_urls = Vec::new();
+            let mut current = Some(url.to_string());
+
+            while let Some(page_url) = current.take() {
+                if visited.len() >= max_pages || !visited.insert(page_url.clone()) {
+                    break;
+                }
+                progress.update(visited.len(), max_pages, "正在解析图片链接");
+                let html = request_ctx.fetch_html(&page_url).await?;
+                let page = parse_rule_page(&rule, &html, &page_url);
+                title = title.or(page.title);
+                image_urls.extend(page.image_urls);
+                current = page.next_page;
+            }
+
+            let image_urls = crate::crawler::parsers::common::url_utils::deduplicate_urls(image_urls);
+            if image_urls.is_empty() {
+                anyhow::bail!("未找到任何图片");
+            }
+
+            progress.set_message("解析完成，准备下载");
+
+            Ok(ParsedGallery {
+                title,
+                image_urls,
+                download_headers: if headers.is_empty() { None } else { Some(headers) },
+                recommended_concurrency: rule.concurrency,
+                metadata: Default::default(),
+                image_transform: None,
+                chapters: Vec::new(),
+            })
+        })
+    }
+}
+
+struct RulePage {
+    title: Option<String>,
+    image_urls: Vec<String>,
+    next_page: Option<String>,
+}
+
+/// 按规则解析单个页面，相对地址以页面地址为基准补全
+fn parse_rule_page(rule: &SiteRule, html: &str, page_url: &str) -> RulePage {
+    let doc = scraper::Html::parse_document(html);
+    let base = url::Url::parse(page_url).ok();
+    let resolve = |href: &str| -> Option<String> {
+        let href = href.trim();
+        if href.is_empty() || href.starts_with("data:") {
+            return None;
+        }
+        match &base {
+            Some(b) => b.join(href).ok().map(|u| u.to_string()),
+            None => Some(href.to_string()),
+        }
+    };
+
+    let title = rule
+        .title_selector
+        .as_deref()
+        .and_then(|s| scraper::Selector::parse(s).ok())
+        .and_then(|sel| doc.select(&sel).next().map(|n| n.text().collect::<String>()))
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+
+    let default_attrs = ["src".to_string()];
+    let attrs: &[String] = if rule.image_attr.is_empty() { &default_attrs } else { &rule.image_attr };
+    let image_urls = scraper::Selector::parse(&rule.image_selector)
+        .map(|sel| {
+            doc.select(&sel)
+                .filter_map(|el| attrs.iter().find_map(|a| el.value().attr(a).and_then(resolve)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let next_page = rule
+        .next_page_selector
+        .as_deref()
+        .and_then(|s| scraper::Selector::parse(s).ok())
+        .and_then(|sel| doc.select(&sel).next().and_then(|a| a.value().attr("href")).and_then(resolve))
+        .filter(|u| u != page_url);
+
+    RulePage { title, image_urls, next_page }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_rule() -> SiteRule {
+        toml::from_str(
+            r##"
+                name = "example"
+                hosts = ["example.com"]
+                title_selector = "h1"
+                image_selector = "#gallery img"
+                image_attr = ["data-src", "src"]
+                next_page_selector = "a.next"
+                referer = "https://example.com/"
+                gallery_id_pattern = "/g/(\\d+)"
+            "##,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_page_with_rule_selectors() {
+        let rule = example_rule();
+        let html = r#"
+            <h1> Title </h1>
+            <div id="gallery">
+                <img data-src="/img/1.jpg" src="data:image/gif;base64,AAAA">
+                <img src="https://cdn.example.com/img/2.jpg">
+            </div>
+            <a class="next" href="?page=2">next</a>
+        "#;
+
+        let page = parse_rule_page(&rule, html, "https://example.com/g/42");
+
+        assert_eq!(page.title.as_deref(), Some("Title"));
+        assert_eq!(page.image_urls, vec!["https://example.com/img/1.jpg", "https://cdn.example.com/img/2.jpg"]);
+        assert_eq!(page.next_page.as_deref(), Some("https://example.com/g/42?page=2"));
+    }
+
+    #[test]
+    fn loads_json_and_toml_rules_from_config_dir() {
+        let dir = std::env::temp_dir().join(format!(
+            "hmanga-rules-test-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        std::fs::create_dir_all(dir.join(RULES_DIR)).unwrap();
+        std::fs::write(dir.join(RULES_DIR).join("a.toml"), toml::to_string(&example_rule()).unwrap()).unwrap();
+        std::fs::write(
+            dir.join(RULES_DIR).join("b.json"),
+            r#"{ "name": "other", "hosts": ["other-rule-test.org"], "image_selector": ".page img" }"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join(RULES_DIR).join("c.json"), r#"{ "name": "bad", "hosts": ["x"], "image_selector": "[[" }"#).unwrap();
+        std::fs::write(
+            dir.join(RULES_DIR).join("d.json"),
+            r#"{ "name": "shadow", "hosts": ["e-hentai.org"], "image_selector": "img" }"#,
+        )
+        .unwrap();
+
+        let report = load_rules_from_dir(&dir);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(report.loaded, vec!["example", "other", "shadow"]);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(crate::crawler::factory::detect_site_type_by_host("www.other-rule-test.org"), Some("custom:other"));
+        assert_eq!(crate::crawler::factory::detect_site_type_by_host("another-rule-test.org"), None);
+        assert_eq!(crate::crawler::factory::detect_site_type_by_host("e-hentai.org"), Some("ehentai"));
+        let parser = crate::crawler::factory::create_for_site("custom:example").unwrap();
+        assert_eq!(parser.gallery_id("https://example.com/g/42").as_deref(), Some("42"));
+    }
+}
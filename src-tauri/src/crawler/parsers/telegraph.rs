@@ -90,6 +90,11 @@ impl TelegraphParser {
 impl SiteParser for TelegraphParser {
     fn name(&self) -> &'static str { "telegraph" }
     fn domains(&self) -> &'static [&'static str] { &["telegra.ph"] }
+    fn gallery_id(&self, url: &str) -> Option<String> {
+        let parsed = url::Url::parse(url).ok()?;
+        let slug = parsed.path_segments()?.find(|s| !s.is_empty())?;
+        Some(slug.to_string())
+    }
     fn parse<'a>(&'a self, client: &'a Client, url: &'a str, reporter: Option<std::sync::Arc<dyn ProgressReporter>>, app_state: Option<&'a crate::AppState>) -> core::pin::Pin<Box<dyn core::future::Future<Output = anyhow::Result<ParsedGallery>> + Send + 'a>> {
         Box::pin(async move {
             // 创建ProgressContext
@@ -205,7 +210,7 @@ impl SiteParser for TelegraphParser {
 
             progress.update(1, 1, "解析完成，准备下载");
 
-            Ok(ParsedGallery { title, image_urls: images, download_headers: None, recommended_concurrency: None })
+            Ok(ParsedGallery { title, image_urls: images, download_headers: None, recommended_concurrency: None, metadata: Default::default(), image_transform: None, chapters: Vec::new() })
         })
     }
 }
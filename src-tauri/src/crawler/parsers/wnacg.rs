@@ -1,28 +1,30 @@
-use crate::crawler::{ParsedGallery, ProgressReporter, SiteParser};
+use crate::crawler::{GalleryMetadata, ParsedGallery, ProgressReporter, SiteParser};
 use crate::progress::ProgressContext;
 use crate::request::Client;
 use crate::config::service::ConfigService;
 use reqwest::header::HeaderMap;
 use std::sync::Arc;
+use once_cell::sync::Lazy;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::time::Duration;
+
+static GALLERY_ID_RE: Lazy<regex::Regex> = Lazy::new(|| regex::Regex::new(r"aid-(\d+)").unwrap());
 
 #[derive(Debug, Clone)]
-struct MangaDetail {
-    detail_url: String,
-    name: String,
+pub(crate) struct MangaDetail {
+    pub(crate) detail_url: String,
+    pub(crate) name: String,
 }
 
-// 从 gallary_wrap ul li 元素中解析漫画详情
-fn parse_manga_detail_from_li(li: &scraper::ElementRef) -> Option<MangaDetail> {
+// 从 gallary_wrap ul li 元素中解析漫画详情；画廊缩略图的名称在 `.name`，列表页的标题在 `.title a`
+pub(crate) fn parse_manga_detail_from_li(li: &scraper::ElementRef) -> Option<MangaDetail> {
     // 查找 img 元素获取 detail_url
     let img_selector = scraper::Selector::parse("a").ok()?;
     let img = li.select(&img_selector).next()?;
 
     let detail_url = img.value().attr("href")?;
 
-    // 查找 class="name" 元素获取名称
-    let name_selector = scraper::Selector::parse(".name").ok()?;
+    // 查找 class="name" 或标题链接获取名称
+    let name_selector = scraper::Selector::parse(".name, .title a").ok()?;
     let name_element = li.select(&name_selector).next()?;
     let name = name_element.text().collect::<String>().trim().to_string();
 
@@ -69,16 +71,16 @@ fn parse_image_url_pattern(first_src: &str, first_name: &str) -> Option<(String,
         let remaining = &first_src[name_pos + first_name.len()..];
 
         tracing::debug!("找到名称位置: name_pos={}, prefix='{}', remaining='{}'", name_pos, prefix, remaining);
-        Some((prefix, ".webp".to_string()))
-        // 查找扩展名（通常是 .webp, .jpg, .png 等）
-        // if let Some(ext_start) = remaining.find('.') {
-        //     let ext = remaining[ext_start..].to_string();
-        //     tracing::debug!("解析成功: prefix='{}', extension='{}'", prefix, ext);
-        //     Some((prefix, ext))
-        // } else {
-        //     tracing::warn!("在剩余部分 '{}' 中未找到扩展名", remaining);
-        //     None
-        // }
+        // 查找扩展名（通常是 .webp, .jpg, .png 等），去掉查询参数
+        let remaining = remaining.split(['?', '#']).next().unwrap_or("");
+        if let Some(ext_start) = remaining.rfind('.') {
+            let ext = remaining[ext_start..].to_string();
+            tracing::debug!("解析成功: prefix='{}', extension='{}'", prefix, ext);
+            Some((prefix, ext))
+        } else {
+            tracing::warn!("在剩余部分 '{}' 中未找到扩展名", remaining);
+            None
+        }
     } else {
         tracing::warn!("在URL '{}' 中未找到名称 '{}'", first_src, first_name);
         None
@@ -101,6 +103,9 @@ impl SiteParser for WnacgParser {
     fn domains(&self) -> &'static [&'static str] {
         &["wnacg.com", "www.wnacg.com"]
     }
+    fn gallery_id(&self, url: &str) -> Option<String> {
+        Some(GALLERY_ID_RE.captures(url)?.get(1)?.as_str().to_string())
+    }
     fn parse<'a>(
         &'a self,
         client: &'a Client,
@@ -134,7 +139,7 @@ impl SiteParser for WnacgParser {
                 anyhow::bail!("状态码异常: {}", first.status());
             }
             let html: String = first.text().await?;
-            let (title_opt, mut page_urls): (Option<String>, Vec<String>) = {
+            let (title_opt, mut page_urls, metadata): (Option<String>, Vec<String>, GalleryMetadata) = {
                 let doc = scraper::Html::parse_document(&html);
                 let title = {
                     let sel = scraper::Selector::parse("#bodywrap > h2").unwrap();
@@ -145,7 +150,15 @@ impl SiteParser for WnacgParser {
                         .filter(|s| !s.is_empty())
                 };
                 let urls = parse_wnacg_pagination(&doc, url);
-                (title, urls)
+                let tags: Vec<String> = scraper::Selector::parse(".addtags a.tagshow")
+                    .map(|sel| {
+                        doc.select(&sel)
+                            .map(|a| a.text().collect::<String>().trim().to_string())
+                            .filter(|s| !s.is_empty())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                (title, urls, GalleryMetadata { tags, ..GalleryMetadata::default() })
             };
             page_urls.sort();
             page_urls.dedup();
@@ -173,9 +186,7 @@ impl SiteParser for WnacgParser {
                         let progress_clone = Arc::clone(&progress_arc);
                         let total_len = page_urls_len;
                         async move {
-                            // 添加随机延迟
-                            random_delay().await;
-
+                            // 请求频率由主机限速器控制（wnacg 默认每秒 1 次）
                             let mut local: Vec<MangaDetail> = vec![];
                             let mut headers_cloned = HeaderMap::new();
                             let _ = headers_cloned.insert("Referer", base_url.as_str().parse().unwrap());
@@ -313,12 +324,16 @@ impl SiteParser for WnacgParser {
                 image_urls,
                 download_headers: None,
                 recommended_concurrency: None,
+                metadata,
+                image_transform: None,
+                chapters: Vec::new(),
             })
         })
     }
 }
 
-fn parse_wnacg_pagination(doc: &scraper::Html, base_url: &str) -> Vec<String> {
+// 解析分页器，返回从第 1 页到最后一页的地址；画廊缩略图页与分类、标签、搜索列表页共用
+pub(crate) fn parse_wnacg_pagination(doc: &scraper::Html, base_url: &str) -> Vec<String> {
     let mut urls = vec![];
     tracing::debug!("解析分页: base_url={}", base_url);
 
@@ -330,7 +345,7 @@ fn parse_wnacg_pagination(doc: &scraper::Html, base_url: &str) -> Vec<String> {
         // 查找所有分页链接，获取最后一页的数字
         for a in doc.select(&sel_pager) {
             if let Some(text) = a.text().next() {
-                if let Ok(num) = text.trim().parse::<i32>() {
+                if let Ok(num) = text.trim().parse::<usize>() {
                     page_numbers.push(num);
                     if num > last_page_num {
                         last_page_num = num;
@@ -343,26 +358,14 @@ fn parse_wnacg_pagination(doc: &scraper::Html, base_url: &str) -> Vec<String> {
 
         // 如果有多页，生成所有分页链接
         if last_page_num > 1 {
-            urls.clear(); // 清空默认的URL
-
-            // 解析基础URL结构
-            // 例如: https://www.wnacg.com/photos-index-aid-317370.html
-            // 需要找到 "-aid-" 的位置，在前面插入 "page-X"
-            if let Some(aid_pos) = base_url.find("-aid-") {
-                let base_part = &base_url[..aid_pos]; // https://www.wnacg.com/photos-index
-                let suffix_part = &base_url[aid_pos..]; // -aid-317370.html
-
-                tracing::debug!("URL结构解析: base_part='{}', suffix_part='{}'", base_part, suffix_part);
-
-                // 生成所有分页链接
-                for page_num in 1..=last_page_num {
-                    let page_url = format!("{}-page-{}{}", base_part, page_num, suffix_part);
-                    urls.push(page_url);
-                }
-                tracing::debug!("生成分页URLs: {}", urls.len());
-            } else {
-                tracing::warn!("无法解析URL结构: 未找到 '-aid-'");
+            urls = (1..=last_page_num)
+                .filter_map(|page_num| wnacg_page_url(base_url, page_num))
+                .collect();
+            if urls.is_empty() {
+                tracing::warn!("无法解析URL结构: 既不是 '-index-' 形式也不是搜索地址");
                 urls.push(base_url.to_string());
+            } else {
+                tracing::debug!("生成分页URLs: {}", urls.len());
             }
         } else {
             tracing::debug!("只有1页或无法解析分页，使用原始URL");
@@ -377,7 +380,35 @@ fn parse_wnacg_pagination(doc: &scraper::Html, base_url: &str) -> Vec<String> {
     urls
 }
 
-fn to_abs_wnacg(u: &str) -> String {
+// 生成第 page_num 页的地址：
+// `photos-index-aid-317370.html` -> `photos-index-page-2-aid-317370.html`，
+// `albums-index-page-3-cate-5.html` -> `albums-index-page-2-cate-5.html`，
+// 搜索页 `/search/?q=x` -> `/search/?q=x&p=2`
+fn wnacg_page_url(base_url: &str, page_num: usize) -> Option<String> {
+    static PAGE_RE: Lazy<regex::Regex> = Lazy::new(|| regex::Regex::new(r"-page-\d+").unwrap());
+    if let Some(index_pos) = base_url.find("-index-") {
+        let (base_part, suffix_part) = base_url.split_at(index_pos + "-index".len());
+        let suffix_part = PAGE_RE.replace(suffix_part, "");
+        return Some(format!("{}-page-{}{}", base_part, page_num, suffix_part));
+    }
+    let mut parsed = url::Url::parse(&to_abs_wnacg(base_url)).ok()?;
+    if !parsed.path().starts_with("/search") {
+        return None;
+    }
+    let pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(k, _)| k != "p")
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    parsed
+        .query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair("p", &page_num.to_string());
+    Some(parsed.to_string())
+}
+
+pub(crate) fn to_abs_wnacg(u: &str) -> String {
     if u.starts_with("http://") || u.starts_with("https://") {
         return u.to_string();
     }
@@ -390,13 +421,6 @@ fn to_abs_wnacg(u: &str) -> String {
     format!("https://www.wnacg.com/{}", u.trim_start_matches("./"))
 }
 
-// 随机延迟函数，延迟 1-3 秒
-async fn random_delay() {
-    use rand::Rng;
-    let delay_ms = rand::thread_rng().gen_range(1000..=3000);
-    tokio::time::sleep(Duration::from_millis(delay_ms)).await;
-}
-
 pub fn register() {
     use crate::crawler::factory::{register, register_host_contains};
     register("wnacg", || Box::new(WnacgParser::new()));
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use chrono::NaiveTime;
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+
+use crate::config::{BandwidthConfig, BandwidthSchedule};
+
+/// 全局令牌桶的键（站点令牌桶以站点类型为键）
+const GLOBAL_KEY: &str = "";
+
+/// 所有下载共享的带宽限制器，每写入一块数据前按当前配置扣减
+#[derive(Default)]
+pub struct BandwidthLimiter {
+    config: RwLock<BandwidthConfig>,
+    buckets: Mutex<HashMap<String, ByteBucket>>,
+}
+
+static LIMITER: Lazy<BandwidthLimiter> = Lazy::new(BandwidthLimiter::default);
+
+pub fn bandwidth_limiter() -> &'static BandwidthLimiter {
+    &LIMITER
+}
+
+/// 字节令牌桶，允许欠账：超出的部分由调用方等待偿还
+struct ByteBucket {
+    rate: u64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl ByteBucket {
+    fn new(rate: u64, now: Instant) -> Self {
+        Self { rate, tokens: rate as f64, last_refill: now }
+    }
+
+    /// 扣减字节数，返回需要等待的时间；桶容量为一秒的流量
+    fn consume(&mut self, rate: u64, bytes: usize, now: Instant) -> Duration {
+        if rate != self.rate {
+            self.rate = rate;
+            self.tokens = self.tokens.min(rate as f64);
+        }
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate as f64).min(rate as f64);
+        self.last_refill = now;
+        self.tokens -= bytes as f64;
+        if self.tokens < 0.0 {
+            Duration::from_secs_f64(-self.tokens / rate as f64)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+/// 校验时间段格式（`HH:MM`）
+pub fn validate_config(config: &BandwidthConfig) -> anyhow::Result<()> {
+    for rule in &config.schedule {
+        for time in [&rule.start, &rule.end] {
+            parse_time(time).ok_or_else(|| anyhow::anyhow!("无效的时间: {}（应为 HH:MM）", time))?;
+        }
+    }
+    Ok(())
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+fn schedule_matches(rule: &BandwidthSchedule, now: NaiveTime) -> bool {
+    let (Some(start), Some(end)) = (parse_time(&rule.start), parse_time(&rule.end)) else {
+        return false;
+    };
+    if start <= end {
+        now >= start && now < end
+    } else {
+        // 跨午夜，例如 22:00-06:00
+        now >= start || now < end
+    }
+}
+
+/// 指定时刻生效的全局上限
+fn global_limit_at(config: &BandwidthConfig, now: NaiveTime) -> Option<u64> {
+    let limit = match config.schedule.iter().find(|rule| schedule_matches(rule, now)) {
+        Some(rule) => rule.global_limit,
+        None => config.global_limit,
+    };
+    limit.filter(|l| *l > 0)
+}
+
+impl BandwidthLimiter {
+    /// 更新配置，正在进行的下载从下一块数据开始按新配置限速
+    pub fn set_config(&self, config: BandwidthConfig) {
+        *self.config.write() = config;
+    }
+
+    /// 写入一块数据前调用，超出上限时等待
+    pub async fn consume(&self, site: Option<&str>, bytes: usize) {
+        let (global, site_limit) = {
+            let config = self.config.read();
+            let global = global_limit_at(&config, chrono::Local::now().time());
+            let site_limit = site
+                .and_then(|s| config.site_limits.get(s).copied())
+                .filter(|l| *l > 0);
+            (global, site_limit)
+        };
+        if global.is_none() && site_limit.is_none() {
+            return;
+        }
+
+        let wait = {
+            let now = Instant::now();
+            let mut buckets = self.buckets.lock();
+            let mut take = |key: &str, rate: u64| {
+                buckets
+                    .entry(key.to_string())
+                    .or_insert_with(|| ByteBucket::new(rate, now))
+                    .consume(rate, bytes, now)
+            };
+            let global_wait = global.map(|rate| take(GLOBAL_KEY, rate)).unwrap_or_default();
+            let site_wait = match (site, site_limit) {
+                (Some(site), Some(rate)) => take(site, rate),
+                _ => Duration::ZERO,
+            };
+            global_wait.max(site_wait)
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(value: &str) -> NaiveTime {
+        parse_time(value).unwrap()
+    }
+
+    fn working_hours_config() -> BandwidthConfig {
+        BandwidthConfig {
+            global_limit: Some(10_000_000),
+            site_limits: HashMap::new(),
+            schedule: vec![
+                BandwidthSchedule { start: "09:00".to_string(), end: "18:00".to_string(), global_limit: Some(2_000_000) },
+                BandwidthSchedule { start: "23:00".to_string(), end: "07:00".to_string(), global_limit: None },
+            ],
+        }
+    }
+
+    #[test]
+    fn schedule_overrides_global_limit_by_time_of_day() {
+        let config = working_hours_config();
+
+        assert_eq!(global_limit_at(&config, time("10:30")), Some(2_000_000));
+        assert_eq!(global_limit_at(&config, time("18:00")), Some(10_000_000));
+        assert_eq!(global_limit_at(&config, time("23:30")), None);
+        assert_eq!(global_limit_at(&config, time("06:59")), None);
+    }
+
+    #[test]
+    fn bucket_delays_bytes_beyond_rate() {
+        let now = Instant::now();
+        let mut bucket = ByteBucket::new(1000, now);
+
+        assert_eq!(bucket.consume(1000, 1000, now), Duration::ZERO);
+        assert_eq!(bucket.consume(1000, 500, now), Duration::from_millis(500));
+        // 欠账偿还后恢复
+        assert_eq!(bucket.consume(1000, 500, now + Duration::from_millis(1500)), Duration::ZERO);
+        // 降低速率时立即生效
+        assert_eq!(bucket.consume(100, 200, now + Duration::from_millis(1500)), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn rejects_invalid_schedule_times() {
+        let mut config = working_hours_config();
+        assert!(validate_config(&config).is_ok());
+
+        config.schedule[0].start = "9am".to_string();
+        assert!(validate_config(&config).is_err());
+    }
+}
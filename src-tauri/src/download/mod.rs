@@ -1,59 +1,85 @@
-use std::path::{Path};
+use std::path::{Path, PathBuf};
 use tokio::io::AsyncWriteExt;
 
 use crate::request::Client as RequestClient;
-use reqwest::header::HeaderMap;
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_RANGE, CONTENT_TYPE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::StatusCode;
 use tracing::{error, warn};
 
+pub mod bandwidth;
+pub mod transform;
+pub mod validate;
+
 #[derive(Clone)]
-pub struct Config { pub retry_count: usize, pub retry_delay_secs: u64 }
-impl Default for Config { fn default() -> Self { Self { retry_count: 3, retry_delay_secs: 2 } } }
+pub struct Config { pub retry_count: usize, pub retry_delay_secs: u64, pub validation: validate::ValidationConfig }
+impl Default for Config { fn default() -> Self { Self { retry_count: 3, retry_delay_secs: 2, validation: validate::ValidationConfig::default() } } }
 
 #[derive(Clone)]
-pub struct Downloader { req: RequestClient, config: Config, default_headers: Option<HeaderMap> }
+pub struct Downloader { req: RequestClient, config: Config, default_headers: Option<HeaderMap>, transform: Option<transform::ImageTransform>, site: Option<&'static str> }
 
 impl Downloader {
     // pub fn new(req: RequestClient, config: Config) -> Self { Self { req, config, default_headers: None } }
-    pub fn new_with_headers(req: RequestClient, config: Config, headers: Option<HeaderMap>) -> Self { Self { req, config, default_headers: headers } }
+    pub fn new_with_headers(req: RequestClient, config: Config, headers: Option<HeaderMap>) -> Self { Self { req, config, default_headers: headers, transform: None, site: None } }
+    /// 设置下载完成后对图片的还原处理
+    pub fn with_transform(mut self, transform: Option<transform::ImageTransform>) -> Self { self.transform = transform; self }
+    /// 设置画廊所属站点，用于按站点限速
+    pub fn with_site(mut self, site: Option<&'static str>) -> Self { self.site = site; self }
 
-    pub async fn download_file(&self, url: &str, file_path: &Path) -> anyhow::Result<()> {
+    /// 下载到同目录的 `.part` 文件，重试时通过 Range 续传，
+    /// 只有在内容长度校验通过后才原子重命名为目标文件。
+    ///
+    /// 目标文件的扩展名以实际内容为准（文件头 > Content-Type > 计划扩展名），
+    /// 返回最终保存路径。
+    pub async fn download_file(&self, url: &str, file_path: &Path) -> anyhow::Result<PathBuf> {
         if let Some(parent) = file_path.parent() { tokio::fs::create_dir_all(parent).await?; }
+        let part_path = part_path_for(file_path);
 
+        // 记录服务端返回的资源校验值（ETag / Last-Modified），续传时用 If-Range 确保资源未变化
+        let mut validator: Option<HeaderValue> = None;
         let mut last_err: Option<anyhow::Error> = None;
         for attempt in 0..=self.config.retry_count {
             if attempt > 0 { tokio::time::sleep(std::time::Duration::from_secs(self.config.retry_delay_secs)).await; }
-            let resp_result = match self.default_headers.as_ref() {
-                Some(h) => self.req.get_with_headers_rate_limited(url, h).await,
-                None => self.req.get_rate_limited(url).await,
-            };
-            match resp_result {
-                Ok(resp) => {
-                    let status = resp.status();
-                    if !status.is_success() {
-                        warn!(attempt = attempt + 1, status = %status, url = %url, "response is not successful");
-                        last_err = Some(anyhow::anyhow!("bad status: {}", status));
-                        continue;
-                    }
-                    // 将流写入文件的过程放入单独分支，错误不直接返回函数，而是记录并进入下一次重试
-                    let write_res = async {
-                        let mut file = tokio::fs::File::create(file_path).await?;
-                        let bytes = resp.bytes().await?;
-                        if let Err(e) = file.write_all(&bytes).await { return Err::<(), anyhow::Error>(e.into()); }
-                        Ok::<(), anyhow::Error>(())
-                    }.await;
-                    match write_res {
-                        Ok(()) => {
-                            return Ok(());
-                        }
+            match self.fetch_to_part(url, &part_path, &mut validator).await {
+                Ok(content_type) => {
+                    let expects_image = self.transform.as_ref().is_none_or(|t| t.expects_image());
+                    let validated = if expects_image {
+                        self.validate_part(&part_path, content_type.clone()).await
+                    } else {
+                        Ok(None)
+                    };
+                    let kind = match validated {
+                        Ok(kind) => kind,
                         Err(e) => {
-                            warn!(attempt = attempt + 1, error = %e, "failed while writing response to file, will retry if attempts remain");
-                            last_err = Some(e);
+                            // 内容无效（HTML 错误页、占位图等），丢弃后重新下载
+                            warn!(attempt = attempt + 1, error = %e, url = %url, "downloaded file failed validation");
+                            let _ = tokio::fs::remove_file(&part_path).await;
+                            last_err = Some(e.into());
                             continue;
                         }
-                    }
+                    };
+                    // 在 .part 上完成还原后再重命名，避免中断时留下未还原的图片
+                    let transformed = match self.apply_transform(url, &part_path).await {
+                        Ok(ext) => ext,
+                        Err(e) => {
+                            error!(error = %e, url = %url, "image transform failed");
+                            let _ = tokio::fs::remove_file(&part_path).await;
+                            return Err(e);
+                        }
+                    };
+                    let ext = transformed.or_else(|| {
+                        kind.or_else(|| validate::sniff_file_kind(&part_path))
+                            .or_else(|| content_type.as_deref().and_then(validate::ImageKind::from_content_type))
+                            .map(|k| k.extension())
+                    });
+                    let final_path = match ext {
+                        Some(ext) => file_path.with_extension(ext),
+                        None => file_path.to_path_buf(),
+                    };
+                    tokio::fs::rename(&part_path, &final_path).await?;
+                    return Ok(final_path);
                 }
                 Err(e) => {
-                    warn!(attempt = attempt + 1, error = %e, "request failed, will retry if attempts remain");
+                    warn!(attempt = attempt + 1, error = %e, url = %url, "download attempt failed, will retry if attempts remain");
                     last_err = Some(e);
                 }
             }
@@ -62,9 +88,157 @@ impl Downloader {
         Err(last_err.unwrap_or_else(|| anyhow::anyhow!("download failed")))
     }
 
+    async fn validate_part(&self, part_path: &Path, content_type: Option<String>) -> Result<Option<validate::ImageKind>, validate::ValidationError> {
+        let config = self.config.validation.clone();
+        if !config.enabled { return Ok(None); }
+        let path = part_path.to_path_buf();
+        tokio::task::spawn_blocking(move || validate::validate_image_file(&path, content_type.as_deref(), &config).map(Some))
+            .await
+            .map_err(|e| validate::ValidationError::Io(e.to_string()))?
+    }
+
+    async fn apply_transform(&self, url: &str, part_path: &Path) -> anyhow::Result<Option<&'static str>> {
+        let Some(transform) = self.transform.clone() else { return Ok(None); };
+        let (url, path) = (url.to_string(), part_path.to_path_buf());
+        tokio::task::spawn_blocking(move || transform.apply(&url, &path)).await?
+    }
+
+    // 单次请求：从 .part 已有长度处续传，写完后校验长度，返回响应的 Content-Type
+    async fn fetch_to_part(&self, url: &str, part_path: &Path, validator: &mut Option<HeaderValue>) -> anyhow::Result<Option<String>> {
+        let offset = tokio::fs::metadata(part_path).await.map(|m| m.len()).unwrap_or(0);
+        let mut headers = self.default_headers.clone().unwrap_or_default();
+        if offset > 0 {
+            headers.insert(RANGE, format!("bytes={}-", offset).parse()?);
+            if let Some(v) = validator.as_ref() { headers.insert(IF_RANGE, v.clone()); }
+        }
+
+        // 连接许可持有到响应体写完，跨任务的连接上限才对下载生效
+        let (mut resp, _permit) = self.req.get_with_headers_holding_connection(url, &headers).await?;
+        let status = resp.status();
+        if status == StatusCode::RANGE_NOT_SATISFIABLE {
+            // 本地残留的 .part 与服务端资源不一致，丢弃后下次从头下载
+            let _ = tokio::fs::remove_file(part_path).await;
+            anyhow::bail!("bad status: {}", status);
+        }
+        if !status.is_success() {
+            anyhow::bail!("bad status: {}", status);
+        }
+        if let Some(v) = strong_validator(resp.headers()) {
+            *validator = Some(v);
+        }
+        let content_type = resp
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+
+        let (mut file, mut written, expected_total) = if status == StatusCode::PARTIAL_CONTENT && offset > 0 {
+            let range = resp
+                .headers()
+                .get(CONTENT_RANGE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_content_range)
+                .ok_or_else(|| anyhow::anyhow!("invalid Content-Range in partial response"))?;
+            if range.start != offset {
+                let _ = tokio::fs::remove_file(part_path).await;
+                anyhow::bail!("unexpected Content-Range start {} (expected {})", range.start, offset);
+            }
+            let file = tokio::fs::OpenOptions::new().append(true).open(part_path).await?;
+            let total = range.total.or_else(|| resp.content_length().map(|len| offset + len));
+            (file, offset, total)
+        } else {
+            // 首次下载或服务端忽略了 Range（资源已变化），从头写入
+            let file = tokio::fs::File::create(part_path).await?;
+            (file, 0, resp.content_length())
+        };
+
+        let limiter = bandwidth::bandwidth_limiter();
+        while let Some(chunk) = resp.chunk().await? {
+            limiter.consume(self.site, chunk.len()).await;
+            file.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        file.flush().await?;
+
+        if let Some(total) = expected_total {
+            if written != total {
+                anyhow::bail!("incomplete body: {}/{} bytes", written, total);
+            }
+        }
+        Ok(content_type)
+    }
+
+}
+
+/// 下载中的临时文件路径，例如 `0001.jpg` -> `0001.jpg.part`
+pub fn part_path_for(file_path: &Path) -> PathBuf {
+    let mut name = file_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    file_path.with_file_name(name)
+}
+
+// If-Range 只接受强校验值，弱 ETag 时退回 Last-Modified
+fn strong_validator(headers: &HeaderMap) -> Option<HeaderValue> {
+    headers
+        .get(ETAG)
+        .filter(|v| !v.as_bytes().starts_with(b"W/"))
+        .or_else(|| headers.get(LAST_MODIFIED))
+        .cloned()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ContentRange { pub start: u64, pub end: u64, pub total: Option<u64> }
+
+/// 解析 `Content-Range: bytes <start>-<end>/<total|*>`
+pub fn parse_content_range(value: &str) -> Option<ContentRange> {
+    let rest = value.trim().strip_prefix("bytes")?.trim_start();
+    let (range, total) = rest.split_once('/')?;
+    let (start, end) = range.split_once('-')?;
+    let total = match total.trim() {
+        "*" => None,
+        t => Some(t.parse().ok()?),
+    };
+    Some(ContentRange { start: start.trim().parse().ok()?, end: end.trim().parse().ok()?, total })
 }
 
 // ---- helpers ----
+/// 恢复中断的任务时，查找计划路径对应且内容有效的已下载文件；
+/// 校验不通过的残留文件（错误页、无关图片等）视为未下载
+pub fn find_valid_download(path: &Path, config: &validate::ValidationConfig) -> Option<PathBuf> {
+    let found = find_downloaded(path)?;
+    if !config.enabled {
+        return Some(found);
+    }
+    let valid = if found.extension().is_some_and(|e| e.eq_ignore_ascii_case("zip")) {
+        // Pixiv 动图保存为原始帧压缩包
+        std::fs::File::open(&found)
+            .and_then(|mut f| {
+                let mut magic = [0u8; 4];
+                std::io::Read::read_exact(&mut f, &mut magic).map(|_| magic)
+            })
+            .is_ok_and(|magic| magic == *b"PK\x03\x04")
+    } else {
+        validate::validate_image_file(&found, None, config).is_ok()
+    };
+    valid.then_some(found)
+}
+
+/// 查找计划路径对应的已下载文件，下载器可能根据实际内容修正了扩展名
+pub fn find_downloaded(path: &Path) -> Option<PathBuf> {
+    let non_empty = |p: &Path| std::fs::metadata(p).map(|m| m.is_file() && m.len() > 0).unwrap_or(false);
+    if non_empty(path) {
+        return Some(path.to_path_buf());
+    }
+    KNOWN_IMAGE_EXTS
+        .iter()
+        .map(|ext| path.with_extension(ext))
+        .find(|candidate| non_empty(candidate))
+}
+
+// `zip` 为保留原始帧的 Pixiv 动图
+const KNOWN_IMAGE_EXTS: &[&str] = &["jpg", "jpeg", "png", "gif", "webp", "bmp", "avif", "zip"];
+
+// 这里的扩展名只是预估，最终以下载器识别出的实际格式为准
 pub fn build_download_plan(image_urls: &[String], base_path: &std::path::Path) -> (Vec<String>, Vec<std::path::PathBuf>) {
     let mut urls: Vec<String> = Vec::with_capacity(image_urls.len());
     let mut paths: Vec<std::path::PathBuf> = Vec::with_capacity(image_urls.len());
@@ -97,4 +271,51 @@ pub fn infer_ext_from_url(url: &str) -> Option<&'static str> {
     None
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_path_appends_suffix_to_full_file_name() {
+        let part = part_path_for(Path::new("D:/manga/gallery/0001.jpg"));
+
+        assert_eq!(part, PathBuf::from("D:/manga/gallery/0001.jpg.part"));
+    }
+
+    #[test]
+    fn parses_content_range_with_known_and_unknown_total() {
+        assert_eq!(
+            parse_content_range("bytes 100-199/1000"),
+            Some(ContentRange { start: 100, end: 199, total: Some(1000) })
+        );
+        assert_eq!(
+            parse_content_range("bytes 100-199/*"),
+            Some(ContentRange { start: 100, end: 199, total: None })
+        );
+        assert_eq!(parse_content_range("items 0-1/2"), None);
+    }
+
+    #[test]
+    fn resume_accepts_only_existing_files_that_pass_validation() {
+        let dir = std::env::temp_dir().join(format!(
+            "hmanga-resume-test-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = validate::ValidationConfig::default();
+        // 内容为 PNG 的页面保存时扩展名已被修正
+        image::RgbImage::from_fn(64, 64, |x, y| image::Rgb([(x * 4) as u8, (y * 4) as u8, (x ^ y) as u8]))
+            .save(dir.join("0001.png"))
+            .unwrap();
+        std::fs::write(dir.join("0002.jpg"), format!("<html><body>{}</body></html>", "x".repeat(512))).unwrap();
 
+        let first = find_valid_download(&dir.join("0001.jpg"), &config);
+        let second = find_valid_download(&dir.join("0002.jpg"), &config);
+        let missing = find_valid_download(&dir.join("0003.jpg"), &config);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(first, Some(dir.join("0001.png")));
+        assert_eq!(second, None);
+        assert_eq!(missing, None);
+    }
+}
@@ -0,0 +1,234 @@
+use std::io::Read;
+use std::path::Path;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::validate::ImageKind;
+
+/// 18comic 页面未提供时使用的默认 scramble_id
+pub const COMIC18_DEFAULT_SCRAMBLE_ID: u64 = 220980;
+
+static COMIC18_PHOTO_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"/media/photos/(\d+)/([^/?#]+?)(?:\.[A-Za-z0-9]+)?(?:[?#]|$)").unwrap());
+
+/// Pixiv 动图的一帧
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UgoiraFrame {
+    pub file: String,
+    /// 毫秒
+    pub delay: u32,
+}
+
+/// Pixiv 动图的保存方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UgoiraFormat {
+    /// 合成为循环播放的 GIF
+    #[default]
+    Gif,
+    /// 保留原始帧压缩包，并在旁边写入帧间隔 JSON
+    Zip,
+}
+
+/// 下载完成后对图片做的还原处理，由解析器通过 `ParsedGallery` 指定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageTransform {
+    /// 18comic（禁漫）切条打乱的图片：按 album id 与页名计算切条数并还原
+    Comic18Descramble { scramble_id: u64 },
+    /// Pixiv 动图：下载的是帧图片压缩包，按帧间隔合成动画
+    PixivUgoira { frames: Vec<UgoiraFrame>, format: UgoiraFormat },
+}
+
+impl ImageTransform {
+    /// 下载内容本身是否为图片（否则跳过图片校验）
+    pub fn expects_image(&self) -> bool {
+        !matches!(self, ImageTransform::PixivUgoira { .. })
+    }
+
+    /// 处理已下载的文件（原地覆盖），返回新的文件扩展名；无需处理时返回 None
+    pub fn apply(&self, url: &str, path: &Path) -> anyhow::Result<Option<&'static str>> {
+        match self {
+            ImageTransform::PixivUgoira { frames, format } => match format {
+                UgoiraFormat::Gif => {
+                    ugoira_to_gif(path, frames)?;
+                    Ok(Some(ImageKind::Gif.extension()))
+                }
+                UgoiraFormat::Zip => {
+                    // `0001.jpg.part` -> `0001.json`
+                    let sidecar = path.with_extension("").with_extension("json");
+                    std::fs::write(sidecar, serde_json::to_string_pretty(&serde_json::json!({ "frames": frames }))?)?;
+                    Ok(Some("zip"))
+                }
+            },
+            ImageTransform::Comic18Descramble { scramble_id } => {
+                let Some((aid, page)) = comic18_photo_id(url) else { return Ok(None); };
+                // GIF 不做切条处理
+                if url.to_ascii_lowercase().contains(".gif") {
+                    return Ok(None);
+                }
+                let segments = comic18_segments(*scramble_id, aid, &page);
+                if segments <= 1 {
+                    return Ok(None);
+                }
+                let img = image::ImageReader::open(path)?.with_guessed_format()?.decode()?.to_rgb8();
+                let restored = comic18_descramble(&img, segments);
+                // 还原后重新编码为 JPEG，WebP 编码器只支持无损，体积过大
+                let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+                image::codecs::jpeg::JpegEncoder::new_with_quality(&mut out, 92).encode_image(&restored)?;
+                Ok(Some(ImageKind::Jpeg.extension()))
+            }
+        }
+    }
+}
+
+/// 将动图帧压缩包合成为 GIF（逐帧编码，避免同时持有所有帧）
+fn ugoira_to_gif(path: &Path, frames: &[UgoiraFrame]) -> anyhow::Result<()> {
+    use image::codecs::gif::{GifEncoder, Repeat};
+    use image::{Delay, Frame};
+
+    if frames.is_empty() {
+        anyhow::bail!("ugoira has no frames");
+    }
+    let mut zip = zip::ZipArchive::new(std::fs::File::open(path)?)?;
+    let mut encoded: Vec<u8> = Vec::new();
+    {
+        let mut encoder = GifEncoder::new_with_speed(&mut encoded, 10);
+        encoder.set_repeat(Repeat::Infinite)?;
+        for frame in frames {
+            let mut buf = Vec::new();
+            zip.by_name(&frame.file)?.read_to_end(&mut buf)?;
+            let rgba = image::load_from_memory(&buf)?.to_rgba8();
+            let delay = Delay::from_numer_denom_ms(frame.delay.max(20), 1);
+            encoder.encode_frame(Frame::from_parts(rgba, 0, 0, delay))?;
+        }
+    }
+    std::fs::write(path, encoded)?;
+    Ok(())
+}
+
+/// 从图片地址提取 (album id, 页名)，例如 `.../media/photos/350234/00001.webp` -> (350234, "00001")
+pub fn comic18_photo_id(url: &str) -> Option<(u64, String)> {
+    let caps = COMIC18_PHOTO_RE.captures(url)?;
+    let aid = caps.get(1)?.as_str().parse().ok()?;
+    Some((aid, caps.get(2)?.as_str().to_string()))
+}
+
+/// 切条数：与站点前端脚本一致，返回 0 表示未打乱
+pub fn comic18_segments(scramble_id: u64, aid: u64, page: &str) -> u32 {
+    if aid < scramble_id {
+        return 0;
+    }
+    if aid < 268850 {
+        return 10;
+    }
+    let modulo = if aid < 421926 { 10 } else { 8 };
+    let digest = format!("{:x}", md5::compute(format!("{}{}", aid, page)));
+    let last = digest.bytes().last().unwrap_or(b'0') as u32;
+    (last % modulo) * 2 + 2
+}
+
+/// 还原切条：原图被横向切成 `segments` 条并倒序排列，余数高度并入第一条
+pub fn comic18_descramble(src: &image::RgbImage, segments: u32) -> image::RgbImage {
+    let (width, height) = src.dimensions();
+    let mut out = image::RgbImage::new(width, height);
+    let over = height % segments;
+    let step = height / segments;
+    for i in 0..segments {
+        let mut len = step;
+        let src_y = height - step * (i + 1) - over;
+        let mut dst_y = step * i;
+        if i == 0 {
+            len += over;
+        } else {
+            dst_y += over;
+        }
+        for y in 0..len {
+            for x in 0..width {
+                out.put_pixel(x, dst_y + y, *src.get_pixel(x, src_y + y));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_album_and_page_from_image_url() {
+        assert_eq!(
+            comic18_photo_id("https://cdn-msp.18comic.vip/media/photos/350234/00001.webp?v=1"),
+            Some((350234, "00001".to_string()))
+        );
+        assert_eq!(comic18_photo_id("https://example.test/00001.webp"), None);
+    }
+
+    #[test]
+    fn segment_count_follows_album_ranges() {
+        assert_eq!(comic18_segments(COMIC18_DEFAULT_SCRAMBLE_ID, 100000, "00001"), 0);
+        assert_eq!(comic18_segments(COMIC18_DEFAULT_SCRAMBLE_ID, 230000, "00001"), 10);
+        assert_eq!(comic18_segments(COMIC18_DEFAULT_SCRAMBLE_ID, 300000, "00001"), 16);
+        assert_eq!(comic18_segments(COMIC18_DEFAULT_SCRAMBLE_ID, 421926, "00010"), 16);
+        assert_eq!(comic18_segments(COMIC18_DEFAULT_SCRAMBLE_ID, 500000, "00002"), 12);
+    }
+
+    #[test]
+    fn assembles_ugoira_frames_into_animated_gif() {
+        let path = std::env::temp_dir().join(format!(
+            "hmanga-ugoira-test-{}.zip.part",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        let mut frames = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+            for (i, shade) in [0u8, 128, 255].into_iter().enumerate() {
+                let img = image::RgbImage::from_pixel(4, 4, image::Rgb([shade, shade, shade]));
+                let mut bytes = std::io::Cursor::new(Vec::new());
+                img.write_to(&mut bytes, image::ImageFormat::Png).unwrap();
+                let file = format!("{:06}.png", i);
+                zip.start_file(file.as_str(), zip::write::SimpleFileOptions::default()).unwrap();
+                std::io::Write::write_all(&mut zip, &bytes.into_inner()).unwrap();
+                frames.push(UgoiraFrame { file, delay: 80 });
+            }
+            zip.finish().unwrap();
+        }
+
+        let transform = ImageTransform::PixivUgoira { frames, format: UgoiraFormat::Gif };
+        let ext = transform.apply("https://i.pximg.net/img-zip-ugoira/1_ugoira600x600.zip", &path).unwrap();
+        let decoded = image::codecs::gif::GifDecoder::new(std::io::BufReader::new(std::fs::File::open(&path).unwrap()))
+            .map(|d| image::AnimationDecoder::into_frames(d).count());
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(ext, Some("gif"));
+        assert_eq!(decoded.unwrap(), 3);
+    }
+
+    #[test]
+    fn segment_count_matches_reference_fixtures() {
+        // 参照 JMComic-Crawler 的 get_num：md5("<aid><页名>") 末位字符的 ASCII 码取模
+        // md5("35023400001") = ...47445ad -> 'd'(100) % 10 * 2 + 2 = 2
+        assert_eq!(comic18_segments(COMIC18_DEFAULT_SCRAMBLE_ID, 350234, "00001"), 2);
+        // md5("35023400002") = ...e5e179c -> 'c'(99) % 10 * 2 + 2 = 20
+        assert_eq!(comic18_segments(COMIC18_DEFAULT_SCRAMBLE_ID, 350234, "00002"), 20);
+        // md5("43869600001") = ...f06eb6 -> '6'(54) % 8 * 2 + 2 = 14
+        assert_eq!(comic18_segments(COMIC18_DEFAULT_SCRAMBLE_ID, 438696, "00001"), 14);
+        // md5("43869600005") = ...f929bc8 -> '8'(56) % 8 * 2 + 2 = 2
+        assert_eq!(comic18_segments(COMIC18_DEFAULT_SCRAMBLE_ID, 438696, "00005"), 2);
+    }
+
+    #[test]
+    fn descramble_restores_reference_stripe_order() {
+        // 高 10、3 条：每条 3 行，余下 1 行并入第一条。
+        // 打乱图自上而下为原图的第 3、2、1 条，还原后第 1 条（4 行）取自打乱图第 6-9 行，
+        // 第 2 条取自第 3-5 行，第 3 条取自第 0-2 行
+        let scrambled = image::RgbImage::from_fn(2, 10, |_, y| image::Rgb([y as u8, 0, 0]));
+
+        let restored = comic18_descramble(&scrambled, 3);
+
+        let rows: Vec<u8> = (0..10).map(|y| restored.get_pixel(1, y)[0]).collect();
+        assert_eq!(rows, vec![6, 7, 8, 9, 3, 4, 5, 0, 1, 2]);
+    }
+}
@@ -0,0 +1,194 @@
+use std::io::Read;
+use std::path::Path;
+
+/// 可识别的图片格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageKind {
+    Jpeg,
+    Png,
+    Gif,
+    Webp,
+    Bmp,
+    Avif,
+}
+
+impl ImageKind {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageKind::Jpeg => "jpg",
+            ImageKind::Png => "png",
+            ImageKind::Gif => "gif",
+            ImageKind::Webp => "webp",
+            ImageKind::Bmp => "bmp",
+            ImageKind::Avif => "avif",
+        }
+    }
+
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let mime = content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+        match mime.as_str() {
+            "image/jpeg" | "image/jpg" | "image/pjpeg" => Some(ImageKind::Jpeg),
+            "image/png" => Some(ImageKind::Png),
+            "image/gif" => Some(ImageKind::Gif),
+            "image/webp" => Some(ImageKind::Webp),
+            "image/bmp" | "image/x-ms-bmp" => Some(ImageKind::Bmp),
+            "image/avif" => Some(ImageKind::Avif),
+            _ => None,
+        }
+    }
+}
+
+/// 读取文件头识别图片格式
+pub fn sniff_file_kind(path: &Path) -> Option<ImageKind> {
+    let mut head = Vec::with_capacity(16);
+    std::fs::File::open(path)
+        .and_then(|f| f.take(16).read_to_end(&mut head))
+        .ok()?;
+    sniff_image_kind(&head)
+}
+
+/// 根据文件头魔数识别图片格式
+pub fn sniff_image_kind(head: &[u8]) -> Option<ImageKind> {
+    if head.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Some(ImageKind::Jpeg);
+    }
+    if head.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some(ImageKind::Png);
+    }
+    if head.starts_with(b"GIF87a") || head.starts_with(b"GIF89a") {
+        return Some(ImageKind::Gif);
+    }
+    if head.len() >= 12 && &head[0..4] == b"RIFF" && &head[8..12] == b"WEBP" {
+        return Some(ImageKind::Webp);
+    }
+    if head.starts_with(b"BM") {
+        return Some(ImageKind::Bmp);
+    }
+    if head.len() >= 12 && &head[4..8] == b"ftyp" && (&head[8..12] == b"avif" || &head[8..12] == b"avis") {
+        return Some(ImageKind::Avif);
+    }
+    None
+}
+
+/// 校验配置
+#[derive(Clone)]
+pub struct ValidationConfig {
+    pub enabled: bool,
+    /// 小于该字节数的文件视为占位图或错误响应
+    pub min_size: u64,
+    /// 是否读取图片头确认尺寸有效
+    pub decode_check: bool,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self { enabled: true, min_size: 256, decode_check: true }
+    }
+}
+
+/// 校验失败原因
+#[derive(Debug)]
+pub enum ValidationError {
+    HtmlResponse,
+    TooSmall(u64),
+    UnknownFormat(String),
+    DecodeFailed(String),
+    Io(String),
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationError::HtmlResponse => write!(f, "invalid image: server returned an html page"),
+            ValidationError::TooSmall(size) => write!(f, "invalid image: file too small ({} bytes)", size),
+            ValidationError::UnknownFormat(head) => write!(f, "invalid image: unknown format (header {})", head),
+            ValidationError::DecodeFailed(msg) => write!(f, "invalid image: decode failed ({})", msg),
+            ValidationError::Io(msg) => write!(f, "invalid image: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+fn looks_like_html(head: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(head);
+    let trimmed = text.trim_start_matches('\u{feff}').trim_start().to_ascii_lowercase();
+    trimmed.starts_with("<!doctype") || trimmed.starts_with("<html") || trimmed.starts_with("<head") || trimmed.starts_with("<body")
+}
+
+/// 校验已下载的文件确实是图片，返回识别出的格式
+pub fn validate_image_file(
+    path: &Path,
+    content_type: Option<&str>,
+    config: &ValidationConfig,
+) -> Result<ImageKind, ValidationError> {
+    let size = std::fs::metadata(path).map_err(|e| ValidationError::Io(e.to_string()))?.len();
+    let mut head = Vec::with_capacity(64);
+    std::fs::File::open(path)
+        .and_then(|f| f.take(64).read_to_end(&mut head))
+        .map_err(|e| ValidationError::Io(e.to_string()))?;
+
+    let is_html_type = content_type
+        .map(|ct| ct.to_ascii_lowercase().starts_with("text/html"))
+        .unwrap_or(false);
+    if is_html_type || looks_like_html(&head) {
+        return Err(ValidationError::HtmlResponse);
+    }
+    if size < config.min_size {
+        return Err(ValidationError::TooSmall(size));
+    }
+    let kind = sniff_image_kind(&head).ok_or_else(|| {
+        let hex: String = head.iter().take(8).map(|b| format!("{:02x}", b)).collect();
+        ValidationError::UnknownFormat(hex)
+    })?;
+
+    // AVIF 不在解码支持范围内，只做魔数校验
+    if config.decode_check && kind != ImageKind::Avif {
+        let (w, h) = image::ImageReader::open(path)
+            .map_err(|e| ValidationError::Io(e.to_string()))?
+            .with_guessed_format()
+            .map_err(|e| ValidationError::Io(e.to_string()))?
+            .into_dimensions()
+            .map_err(|e| ValidationError::DecodeFailed(e.to_string()))?;
+        if w == 0 || h == 0 {
+            return Err(ValidationError::DecodeFailed(format!("invalid dimensions {}x{}", w, h)));
+        }
+    }
+    Ok(kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniffs_common_image_signatures() {
+        assert_eq!(sniff_image_kind(&[0xFF, 0xD8, 0xFF, 0xE0]), Some(ImageKind::Jpeg));
+        assert_eq!(sniff_image_kind(b"\x89PNG\r\n\x1a\n...."), Some(ImageKind::Png));
+        assert_eq!(sniff_image_kind(b"GIF89a"), Some(ImageKind::Gif));
+        assert_eq!(sniff_image_kind(b"RIFF\x10\x00\x00\x00WEBPVP8 "), Some(ImageKind::Webp));
+        assert_eq!(sniff_image_kind(b"\x00\x00\x00\x1cftypavif"), Some(ImageKind::Avif));
+        assert_eq!(sniff_image_kind(b"<html>"), None);
+    }
+
+    #[test]
+    fn maps_content_type_with_parameters_to_kind() {
+        assert_eq!(ImageKind::from_content_type("image/webp"), Some(ImageKind::Webp));
+        assert_eq!(ImageKind::from_content_type("Image/JPEG; charset=binary"), Some(ImageKind::Jpeg));
+        assert_eq!(ImageKind::from_content_type("application/octet-stream"), None);
+    }
+
+    #[test]
+    fn rejects_html_error_page_saved_as_image() {
+        let path = std::env::temp_dir().join(format!(
+            "hmanga-validate-test-{}.jpg",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        std::fs::write(&path, "  <!DOCTYPE html><html><body>429 Too Many Requests</body></html>").unwrap();
+
+        let result = validate_image_file(&path, Some("image/jpeg"), &ValidationConfig::default());
+        let _ = std::fs::remove_file(&path);
+
+        assert!(matches!(result, Err(ValidationError::HtmlResponse)));
+    }
+}
@@ -0,0 +1,159 @@
+use std::path::{Path, PathBuf};
+
+use crate::crawler::GalleryMetadata;
+
+pub const COMIC_INFO_FILE: &str = "ComicInfo.xml";
+
+/// ComicInfo.xml（ComicRack v2 schema，Kavita / Komga 可识别）
+#[derive(Debug, Clone, Default)]
+pub struct ComicInfo {
+    pub title: String,
+    pub metadata: GalleryMetadata,
+    pub page_count: usize,
+}
+
+impl ComicInfo {
+    pub fn new(title: &str, metadata: &GalleryMetadata, page_count: usize) -> Self {
+        Self {
+            title: title.to_string(),
+            metadata: metadata.clone(),
+            page_count: if page_count > 0 { page_count } else { metadata.page_count.unwrap_or(0) },
+        }
+    }
+
+    pub fn to_xml(&self) -> String {
+        let m = &self.metadata;
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<ComicInfo xmlns:xsd=\"http://www.w3.org/2001/XMLSchema\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">\n",
+        );
+        // 元素顺序遵循 schema 定义
+        push_element(&mut xml, "Title", &self.title);
+        // 每个画廊单独作为一个系列，避免媒体服务器把不同画廊合并
+        push_element(&mut xml, "Series", &self.title);
+        push_element(&mut xml, "AlternateSeries", m.original_title.as_deref().unwrap_or(""));
+        push_element(&mut xml, "Writer", &m.artists.join(", "));
+        push_element(&mut xml, "Penciller", &m.artists.join(", "));
+        push_element(&mut xml, "Genre", m.category.as_deref().unwrap_or(""));
+        push_element(&mut xml, "Tags", &m.tags.join(", "));
+        push_element(&mut xml, "Web", m.source_url.as_deref().unwrap_or(""));
+        if self.page_count > 0 {
+            push_element(&mut xml, "PageCount", &self.page_count.to_string());
+        }
+        push_element(&mut xml, "LanguageISO", language_iso(m.language.as_deref()).unwrap_or(""));
+        push_element(&mut xml, "Manga", "YesAndRightToLeft");
+        push_element(&mut xml, "Characters", &m.characters.join(", "));
+        push_element(&mut xml, "Teams", &m.groups.join(", "));
+        push_element(&mut xml, "SeriesGroup", &m.parodies.join(", "));
+        push_element(&mut xml, "AgeRating", "Adults Only 18+");
+        xml.push_str("</ComicInfo>\n");
+        xml
+    }
+
+    /// 写入画廊目录，返回文件路径
+    pub fn write_to_dir(&self, dir: &Path) -> anyhow::Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(COMIC_INFO_FILE);
+        std::fs::write(&path, self.to_xml())?;
+        Ok(path)
+    }
+}
+
+fn push_element(xml: &mut String, name: &str, value: &str) {
+    let value = value.trim();
+    if value.is_empty() {
+        return;
+    }
+    xml.push_str(&format!("  <{}>{}</{}>\n", name, escape_xml(value), name));
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // XML 1.0 不允许的控制字符直接丢弃
+            c if (c as u32) < 0x20 && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// 读取 ComicInfo.xml 中某个元素的文本（只处理本模块写出的扁平结构）
+pub fn read_element(xml: &str, name: &str) -> Option<String> {
+    let open = format!("<{}>", name);
+    let close = format!("</{}>", name);
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find(&close)?;
+    let value = unescape_xml(xml[start..end].trim());
+    if value.is_empty() { None } else { Some(value) }
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn language_iso(language: Option<&str>) -> Option<&'static str> {
+    match language?.trim().to_ascii_lowercase().as_str() {
+        "japanese" | "日本語" => Some("ja"),
+        "english" => Some("en"),
+        "chinese" | "中文" => Some("zh"),
+        "korean" | "한국어" => Some("ko"),
+        "spanish" | "español" => Some("es"),
+        "french" | "français" => Some("fr"),
+        "german" | "deutsch" => Some("de"),
+        "russian" | "русский" => Some("ru"),
+        "portuguese" | "português" => Some("pt"),
+        "italian" | "italiano" => Some("it"),
+        "thai" | "ไทย" => Some("th"),
+        "vietnamese" | "tiếng việt" => Some("vi"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_escaped_fields_and_skips_empty_ones() {
+        let metadata = GalleryMetadata {
+            source_url: Some("https://example.test/g/1?a=1&b=2".to_string()),
+            artists: vec!["A & B".to_string()],
+            tags: vec!["female:glasses".to_string(), "full color".to_string()],
+            language: Some("Japanese".to_string()),
+            ..GalleryMetadata::default()
+        };
+
+        let xml = ComicInfo::new("<Title>", &metadata, 12).to_xml();
+
+        assert!(xml.contains("<Title>&lt;Title&gt;</Title>"));
+        assert!(xml.contains("<Writer>A &amp; B</Writer>"));
+        assert!(xml.contains("<Tags>female:glasses, full color</Tags>"));
+        assert!(xml.contains("<Web>https://example.test/g/1?a=1&amp;b=2</Web>"));
+        assert!(xml.contains("<PageCount>12</PageCount>"));
+        assert!(xml.contains("<LanguageISO>ja</LanguageISO>"));
+        assert!(!xml.contains("<Characters>"));
+    }
+
+    #[test]
+    fn read_element_unescapes_written_values() {
+        let metadata = GalleryMetadata {
+            source_url: Some("https://example.test/g/1?a=1&b=2".to_string()),
+            ..GalleryMetadata::default()
+        };
+        let xml = ComicInfo::new("t", &metadata, 1).to_xml();
+
+        assert_eq!(read_element(&xml, "Web").as_deref(), Some("https://example.test/g/1?a=1&b=2"));
+        assert_eq!(read_element(&xml, "Tags"), None);
+    }
+}
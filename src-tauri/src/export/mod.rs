@@ -0,0 +1,118 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use zip::write::SimpleFileOptions;
+use zip::CompressionMethod;
+
+use crate::library;
+
+pub mod comic_info;
+
+/// CBZ 导出选项
+#[derive(Debug, Clone, Default)]
+pub struct CbzOptions {
+    /// 导出成功后删除画廊目录中的散图
+    pub delete_source: bool,
+}
+
+/// 画廊目录对应的 CBZ 路径：与目录同级，例如 `out/gallery` -> `out/gallery.cbz`
+pub fn cbz_path_for(gallery_dir: &Path) -> PathBuf {
+    let mut name = gallery_dir.file_name().unwrap_or_default().to_os_string();
+    name.push(".cbz");
+    gallery_dir.with_file_name(name)
+}
+
+/// 将画廊目录打包为 CBZ，页面顺序与书库排序一致，返回生成的文件路径
+pub fn export_cbz(gallery_dir: &Path, options: &CbzOptions) -> anyhow::Result<PathBuf> {
+    let mgr = library::Manager::default();
+    let images = mgr.get_manga_images(&gallery_dir.to_string_lossy())?;
+    if images.is_empty() {
+        anyhow::bail!("目录中没有图片: {}", gallery_dir.display());
+    }
+
+    let target = cbz_path_for(gallery_dir);
+    let part = target.with_extension("cbz.part");
+    {
+        let file = fs::File::create(&part)?;
+        let mut zip = zip::ZipWriter::new(file);
+        // 图片本身已压缩，直接存储即可
+        let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        let width = images.len().to_string().len().max(4);
+        for (idx, image) in images.iter().enumerate() {
+            let path = Path::new(image);
+            let ext = path
+                .extension()
+                .and_then(|s| s.to_str())
+                .unwrap_or("jpg")
+                .to_ascii_lowercase();
+            // 按排序结果重新编号，保证其他阅读器按文件名排序时顺序一致
+            let entry_name = format!("{:0width$}.{}", idx + 1, ext, width = width);
+            zip.start_file(entry_name, stored)?;
+            zip.write_all(&fs::read(path)?)?;
+        }
+        // 一并打包元数据，供媒体服务器识别
+        let comic_info = gallery_dir.join(comic_info::COMIC_INFO_FILE);
+        if comic_info.is_file() {
+            zip.start_file(comic_info::COMIC_INFO_FILE, SimpleFileOptions::default())?;
+            zip.write_all(&fs::read(&comic_info)?)?;
+        }
+        zip.finish()?;
+    }
+    fs::rename(&part, &target)?;
+
+    if options.delete_source {
+        for image in &images {
+            let _ = fs::remove_file(image);
+        }
+        let _ = fs::remove_file(gallery_dir.join(comic_info::COMIC_INFO_FILE));
+        // 目录中仍有其他文件时保留目录
+        let _ = fs::remove_dir(gallery_dir);
+    }
+
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cbz_path_is_sibling_of_gallery_dir() {
+        assert_eq!(
+            cbz_path_for(Path::new("D:/manga/gallery")),
+            PathBuf::from("D:/manga/gallery.cbz")
+        );
+    }
+
+    #[test]
+    fn export_writes_pages_in_library_order_and_removes_sources() {
+        let root = std::env::temp_dir().join(format!(
+            "hmanga-export-test-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        let dir = root.join("gallery");
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["10.jpg", "2.png", "1.jpg"] {
+            fs::write(dir.join(name), name.as_bytes()).unwrap();
+        }
+
+        let cbz = export_cbz(&dir, &CbzOptions { delete_source: true }).unwrap();
+        let mut archive = zip::ZipArchive::new(fs::File::open(&cbz).unwrap()).unwrap();
+        let names: Vec<String> = (0..archive.len())
+            .map(|i| archive.by_index(i).unwrap().name().to_string())
+            .collect();
+        let first = {
+            let mut entry = archive.by_index(0).unwrap();
+            let mut buf = String::new();
+            std::io::Read::read_to_string(&mut entry, &mut buf).unwrap();
+            buf
+        };
+        let dir_removed = !dir.exists();
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(names, vec!["0001.jpg", "0002.png", "0003.jpg"]);
+        assert_eq!(first, "1.jpg");
+        assert!(dir_removed);
+    }
+}
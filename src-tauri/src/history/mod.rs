@@ -24,6 +24,9 @@ pub struct DownloadTaskDTO {
     pub progress: Progress,
     #[serde(default = "default_retryable")]
     pub retryable: bool,
+    /// 多章节画廊选择下载的章节，为空表示全部
+    #[serde(default)]
+    pub chapters: Vec<usize>,
 }
 
 fn default_retryable() -> bool {
@@ -5,10 +5,13 @@ use tokio_util::sync::CancellationToken;
 use std::collections::HashMap;
 
 use crate::config::service::{AppConfigService, ConfigService};
+use crate::library::index::LibraryIndex;
 use crate::logger::Logger;
 use crate::request::RequestClient;
+use crate::request::site::SiteClients;
 use crate::task::TaskManager;
 use crate::services::TaskService;
+use crate::subscription::SubscriptionStore;
 
 mod commands;
 mod logger;
@@ -22,15 +25,20 @@ mod crawler;
 mod progress;
 mod batch_crawler;
 mod services;
+mod export;
+mod subscription;
 
 #[derive(Clone)]
 pub struct AppState {
     pub logger: Arc<Logger>,
     pub config: Arc<RwLock<AppConfigService>>,
     pub request: Arc<RwLock<RequestClient>>,
+    pub site_clients: SiteClients,
     pub cancels: Arc<RwLock<HashMap<String, CancellationToken>>>,
     pub task_manager: Arc<RwLock<TaskManager>>,
     pub task_service: Arc<TaskService>,
+    pub library_index: Arc<RwLock<LibraryIndex>>,
+    pub subscriptions: Arc<RwLock<SubscriptionStore>>,
 }
 
 impl Default for AppState {
@@ -39,9 +47,12 @@ impl Default for AppState {
             logger: Arc::new(Logger::default()),
             config: Arc::new(RwLock::new(AppConfigService::default())),
             request: Arc::new(RwLock::new(RequestClient::new(None).unwrap())),
+            site_clients: SiteClients::default(),
             cancels: Arc::new(RwLock::new(HashMap::new())),
             task_manager: Arc::new(RwLock::new(TaskManager::default())),
             task_service: Arc::new(TaskService::new()),
+            library_index: Arc::new(RwLock::new(LibraryIndex::default())),
+            subscriptions: Arc::new(RwLock::new(SubscriptionStore::default())),
         }
     }
 }
@@ -57,30 +68,85 @@ impl AppState {
     }
 
     pub fn init_config(&self, handle: AppHandle) -> anyhow::Result<()> {
-        let max_concurrent_tasks = {
+        let (max_concurrent_tasks, connections, bandwidth) = {
             let mut config = self.config.write();
             config.set_path_from_app(&handle)?;
             config.load_or_default()?;
 
             // 获取并发限制配置
-            config.get_max_concurrent_tasks()
+            (config.get_max_concurrent_tasks(), config.get_connection_config(), config.get_bandwidth_config())
         };
+        request::scheduler::connection_scheduler().set_config(connections);
+        download::bandwidth::bandwidth_limiter().set_config(bandwidth);
 
         // 初始化任务管理器的并发限制
         self.task_manager.write().set_max_concurrent_tasks(max_concurrent_tasks);
 
+        // 恢复上次退出时未完成的任务，由队列处理器重新执行
+        {
+            let task_manager = self.task_manager.read();
+            task_manager.journal.set_dir_from_app(&handle)?;
+            let restored = task_manager.restore_unfinished();
+            if restored > 0 {
+                tracing::info!("restored {} unfinished tasks from journal", restored);
+            }
+        }
+
+        self.library_index.write().set_dir_from_app(&handle)?;
+        self.subscriptions.write().set_dir_from_app(&handle)?;
+        // cookie 文件损坏时已备份原文件，继续以空存储启动
+        if let Err(e) = request::cookies::cookie_jar().set_dir_from_app(&handle) {
+            tracing::error!(error = %e, "failed to load cookies");
+        }
+        self.reload_parser_rules();
+
         self.rebuild_request_client()?;
         Ok(())
     }
 
+    /// 加载配置目录下 `parsers/` 中的自定义站点规则
+    pub fn reload_parser_rules(&self) -> crawler::parsers::rule::RuleLoadReport {
+        let config_path = std::path::PathBuf::from(self.config.read().get_config_path());
+        let config_dir = config_path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+        let report = crawler::parsers::rule::load_rules_from_dir(&config_dir);
+        for (file, error) in &report.errors {
+            tracing::warn!("failed to load parser rule {}: {}", file, error);
+        }
+        report
+    }
+
     pub fn rebuild_request_client(&self) -> anyhow::Result<()> {
         let proxy = self.config.read().get_proxy();
         let proxy_opt = if proxy.is_empty() { None } else { Some(proxy) };
         let client = RequestClient::new(proxy_opt)?;
         *self.request.write() = client;
+        self.site_clients.clear();
         Ok(())
     }
 
+    /// URL 所属站点的请求客户端；站点没有单独配置时使用全局客户端
+    pub fn client_for_url(&self, url: &str) -> anyhow::Result<RequestClient> {
+        let Some(site) = crawler::site_type_for_url(url) else {
+            return Ok(self.request.read().clone());
+        };
+        let (site_config, proxy) = {
+            let config = self.config.read();
+            (config.get_all_parser_configs().remove(site), config.get_proxy())
+        };
+        // 同步站点速率到共享的主机限速器
+        let rate = site_config
+            .as_ref()
+            .and_then(|c| request::rate_limit::RateLimit::from_config(c.base.rate_limit, c.base.rate_burst))
+            .or_else(|| request::rate_limit::default_site_rate(site));
+        request::rate_limit::host_limiter().set_site_rate(site, rate);
+        match site_config {
+            Some(site_config) => self
+                .site_clients
+                .get(site, request::site::options_for_site(&site_config.base, &proxy)),
+            None => Ok(self.request.read().clone()),
+        }
+    }
+
     /// 启动定期队列处理器
     fn start_queue_processor(app: tauri::AppHandle, state: tauri::State<'_, AppState>) {
         // 创建AppState的深拷贝，包含所有Arc字段的克隆
@@ -88,9 +154,12 @@ impl AppState {
             logger: state.logger.clone(),
             config: state.config.clone(),
             request: state.request.clone(),
+            site_clients: state.site_clients.clone(),
             cancels: state.cancels.clone(),
             task_manager: state.task_manager.clone(),
             task_service: state.task_service.clone(),
+            library_index: state.library_index.clone(),
+            subscriptions: state.subscriptions.clone(),
         };
 
         tauri::async_runtime::spawn(async move {
@@ -112,6 +181,20 @@ impl AppState {
             }
         });
     }
+
+    /// 启动订阅检查定时器，每分钟检查一次到期的订阅
+    fn start_subscription_scheduler(app: tauri::AppHandle, state: tauri::State<'_, AppState>) {
+        let state_clone = state.inner().clone();
+
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+
+            loop {
+                interval.tick().await;
+                crate::services::SubscriptionService::check_due(&app, &state_clone).await;
+            }
+        });
+    }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -127,7 +210,13 @@ pub fn run() {
             state.init_config(app_handle.clone())?;
 
             // 启动定期队列处理器
-            AppState::start_queue_processor(app_handle.clone(), state);
+            AppState::start_queue_processor(app_handle.clone(), state.clone());
+
+            // 启动订阅检查定时器
+            AppState::start_subscription_scheduler(app_handle.clone(), state);
+
+            // 任务完成后按配置自动导出 CBZ
+            crate::services::ExportService::register_auto_export(app_handle);
 
             Ok(())
         })
@@ -147,6 +236,14 @@ pub fn run() {
             commands::config_get_config_path,
             commands::config_get_max_concurrent_tasks,
             commands::config_set_max_concurrent_tasks,
+            commands::config_get_export_config,
+            commands::config_set_export_config,
+            commands::config_get_duplicate_policy,
+            commands::config_set_duplicate_policy,
+            commands::config_get_connection_config,
+            commands::config_set_connection_config,
+            commands::config_get_bandwidth_config,
+            commands::config_set_bandwidth_config,
             // logger
             commands::logger_get_info,
             // library
@@ -156,6 +253,11 @@ pub fn run() {
             commands::library_load_all,
             commands::library_get_all_mangas,
             commands::library_get_manga_images,
+            commands::library_read_page,
+            commands::library_index_refresh,
+            commands::library_index_get_all,
+            commands::library_search,
+            commands::library_find_duplicates,
             commands::library_delete_manga,
             // history
             commands::history_get,
@@ -175,9 +277,33 @@ pub fn run() {
             commands::task_get_status,
             // crawler
             commands::task_start_crawl,
+            commands::crawl_list_chapters,
+            commands::crawl_reload_parser_rules,
             // batch
             commands::batch_start_crawl,
+            commands::batch_preview,
+            commands::batch_enqueue,
+            // subscription
+            commands::subscription_list,
+            commands::subscription_add,
+            commands::subscription_update,
+            commands::subscription_remove,
+            commands::subscription_check_now,
+            // cookies
+            commands::cookies_import,
+            commands::cookies_clear,
+            commands::cookies_session_status,
+            // export
+            commands::export_cbz,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app, event| {
+            // 写入尚未保存的 Set-Cookie 变化
+            if let tauri::RunEvent::Exit = event {
+                if let Err(e) = request::cookies::cookie_jar().flush() {
+                    tracing::warn!(error = %e, "failed to save cookies");
+                }
+            }
+        });
 }
@@ -0,0 +1,127 @@
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use base64::Engine;
+
+/// 压缩包内页面引用的分隔符：`D:/manga/a.cbz!/0001.jpg`
+pub const PAGE_SEPARATOR: &str = "!/";
+
+/// 单个条目的最大读取字节数，条目头中的大小不可信，避免按其一次分配大块内存
+const MAX_PAGE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// 是否为可作为漫画读取的压缩包
+pub fn is_archive_file(p: &Path) -> bool {
+    match p.extension().and_then(|s| s.to_str()).map(|s| s.to_ascii_lowercase()) {
+        Some(ext) => matches!(ext.as_str(), "cbz" | "zip"),
+        None => false,
+    }
+}
+
+/// 拼接压缩包内页面引用
+pub fn page_ref(archive: &Path, entry: &str) -> String {
+    format!("{}{}{}", archive.to_string_lossy(), PAGE_SEPARATOR, entry)
+}
+
+/// 拆分页面引用为 (压缩包路径, 条目名)，普通文件路径返回 None
+pub fn split_page_ref(page: &str) -> Option<(&str, &str)> {
+    let idx = page.find(PAGE_SEPARATOR)?;
+    let (archive, entry) = (&page[..idx], &page[idx + PAGE_SEPARATOR.len()..]);
+    if entry.is_empty() || !is_archive_file(Path::new(archive)) {
+        return None;
+    }
+    Some((archive, entry))
+}
+
+/// 列出压缩包中的图片条目名（未排序）
+pub fn image_entries(archive: &Path) -> anyhow::Result<Vec<String>> {
+    let mut zip = zip::ZipArchive::new(fs::File::open(archive)?)?;
+    let mut entries = vec![];
+    for i in 0..zip.len() {
+        let entry = zip.by_index(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        // 跳过 macOS 打包时附带的资源文件
+        if name.starts_with("__MACOSX/") {
+            continue;
+        }
+        if super::is_image_file(Path::new(&name)) {
+            entries.push(name);
+        }
+    }
+    Ok(entries)
+}
+
+/// 读取压缩包中单个条目的内容，不解压到磁盘
+pub fn read_entry(archive: &Path, entry: &str) -> anyhow::Result<Vec<u8>> {
+    let mut zip = zip::ZipArchive::new(fs::File::open(archive)?)?;
+    let file = zip.by_name(entry)?;
+    let mut buf = Vec::with_capacity(file.size().min(MAX_PAGE_BYTES) as usize);
+    file.take(MAX_PAGE_BYTES + 1).read_to_end(&mut buf)?;
+    if buf.len() as u64 > MAX_PAGE_BYTES {
+        anyhow::bail!("压缩包条目过大: {}", entry);
+    }
+    Ok(buf)
+}
+
+/// 读取页面内容，支持普通文件和压缩包内页面
+pub fn read_page(page: &str) -> anyhow::Result<Vec<u8>> {
+    match split_page_ref(page) {
+        Some((archive, entry)) => read_entry(Path::new(archive), entry),
+        None => Ok(fs::read(page)?),
+    }
+}
+
+/// 读取页面内容并返回 data URL
+pub fn read_page_data_url(page: &str) -> anyhow::Result<String> {
+    let bytes = read_page(page)?;
+    let name = split_page_ref(page).map(|(_, entry)| entry).unwrap_or(page);
+    let mime = mime_guess::from_path(name).first_or_octet_stream();
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+    Ok(format!("data:{};base64,{}", mime, encoded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn splits_archive_page_ref_but_not_plain_paths() {
+        assert_eq!(
+            split_page_ref("D:/manga/a.cbz!/sub/0001.jpg"),
+            Some(("D:/manga/a.cbz", "sub/0001.jpg"))
+        );
+        assert_eq!(split_page_ref("D:/manga/a!/0001.jpg"), None);
+        assert_eq!(split_page_ref("D:/manga/a/0001.jpg"), None);
+    }
+
+    #[test]
+    fn lists_and_reads_image_entries_inside_archive() {
+        let path = std::env::temp_dir().join(format!(
+            "hmanga-archive-test-{}.cbz",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        {
+            let mut zip = zip::ZipWriter::new(fs::File::create(&path).unwrap());
+            let options = zip::write::SimpleFileOptions::default();
+            for name in ["2.png", "1.jpg", "ComicInfo.xml", "__MACOSX/._1.jpg"] {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(name.as_bytes()).unwrap();
+            }
+            zip.finish().unwrap();
+        }
+
+        let mut entries = image_entries(&path).unwrap();
+        entries.sort();
+        let data = read_entry(&path, "1.jpg").unwrap();
+        let url = read_page_data_url(&page_ref(&path, "2.png")).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(entries, vec!["1.jpg", "2.png"]);
+        assert_eq!(data, b"1.jpg");
+        assert!(url.starts_with("data:image/png;base64,"));
+    }
+}
@@ -0,0 +1,324 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::Manager as TauriManager;
+
+use super::{archive, MangaKind, Manager};
+use crate::export::comic_info::{self, COMIC_INFO_FILE};
+
+const INDEX_FILE: &str = "library_index.json";
+
+/// 书库索引中的一部漫画
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexEntry {
+    pub path: String,
+    pub name: String,
+    pub kind: MangaKind,
+    /// 所属书库根目录
+    pub library: String,
+    pub preview_img: String,
+    pub images_count: usize,
+    /// 占用空间（字节）
+    pub size: u64,
+    /// 目录或压缩包的修改时间（毫秒），用于增量扫描
+    pub mtime: i64,
+    /// 入库时间（毫秒），优先取下载历史中的完成时间，其次为创建时间，不支持时退回修改时间
+    #[serde(default)]
+    pub added_at: i64,
+    #[serde(default)]
+    pub source_url: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub artists: Vec<String>,
+    /// ComicInfo.xml 中的标题（原始画廊标题，可能与目录名不同）
+    #[serde(default)]
+    pub title: Option<String>,
+    pub indexed_at: String,
+}
+
+/// 一次刷新的统计
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshStats {
+    pub total: usize,
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+/// 持久化的书库索引
+///
+/// 以路径为键缓存每部漫画的页数、封面、大小和 ComicInfo.xml 中的来源与标签，
+/// 刷新时只重新读取修改时间发生变化的目录和压缩包。
+#[derive(Clone, Default)]
+pub struct LibraryIndex {
+    path: Option<PathBuf>,
+    entries: HashMap<String, IndexEntry>,
+}
+
+impl LibraryIndex {
+    pub fn set_dir_from_app(&mut self, app: &tauri::AppHandle) -> anyhow::Result<()> {
+        #[allow(deprecated)]
+        let base = app
+            .path()
+            .app_data_dir()
+            .unwrap_or(std::env::temp_dir());
+        self.set_dir(base);
+        Ok(())
+    }
+
+    /// 设置索引目录并加载已有索引
+    pub fn set_dir(&mut self, dir: PathBuf) {
+        self.path = Some(dir.join(INDEX_FILE));
+        let _ = self.load();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, path: &str) -> Option<&IndexEntry> {
+        self.entries.get(path)
+    }
+
+    /// 返回属于指定书库的条目，按路径排序
+    pub fn entries(&self, roots: &[String]) -> Vec<IndexEntry> {
+        let mut list: Vec<IndexEntry> = self
+            .entries
+            .values()
+            .filter(|e| roots.contains(&e.library))
+            .cloned()
+            .collect();
+        list.sort_by(|a, b| a.path.cmp(&b.path));
+        list
+    }
+
+    pub fn remove(&mut self, path: &str) {
+        if self.entries.remove(path).is_some() {
+            let _ = self.save();
+        }
+    }
+
+    /// 增量刷新：未变化的条目直接复用，`force` 为 true 时全部重新读取；
+    /// `download_times` 为下载历史中各保存路径的完成时间（毫秒）
+    pub fn refresh(&mut self, roots: &[String], force: bool, download_times: &HashMap<String, i64>) -> RefreshStats {
+        let mgr = Manager::default();
+        let mut stats = RefreshStats::default();
+        let mut seen: HashSet<String> = HashSet::new();
+
+        for root in roots {
+            for (path, kind) in mgr.discover(root) {
+                let key = path.to_string_lossy().to_string();
+                let mtime = modified_millis(&path);
+                if let Some(existing) = self.entries.get_mut(&key) {
+                    if !force && existing.mtime == mtime && existing.kind == kind && &existing.library == root {
+                        if existing.added_at == 0 {
+                            // 旧版本索引没有入库时间
+                            existing.added_at = added_at_for(&path, &key, mtime, download_times);
+                            stats.updated += 1;
+                        } else {
+                            stats.unchanged += 1;
+                        }
+                        seen.insert(key);
+                        continue;
+                    }
+                }
+                match build_entry(&mgr, &path, kind, root, mtime) {
+                    Ok(Some(mut entry)) => {
+                        // 内容变化不影响入库时间
+                        entry.added_at = match self.entries.get(&key) {
+                            Some(existing) if existing.added_at != 0 => existing.added_at,
+                            _ => added_at_for(&path, &key, mtime, download_times),
+                        };
+                        if self.entries.insert(key.clone(), entry).is_some() {
+                            stats.updated += 1;
+                        } else {
+                            stats.added += 1;
+                        }
+                        seen.insert(key);
+                    }
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!(path = %path.display(), error = %e, "skip unreadable manga"),
+                }
+            }
+        }
+
+        let before = self.entries.len();
+        self.entries.retain(|k, _| seen.contains(k));
+        stats.removed = before - self.entries.len();
+        stats.total = self.entries.len();
+        if let Err(e) = self.save() {
+            tracing::warn!(error = %e, "failed to save library index");
+        }
+        stats
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else { return Ok(()); };
+        if let Some(p) = path.parent() { fs::create_dir_all(p)?; }
+        let mut list: Vec<&IndexEntry> = self.entries.values().collect();
+        list.sort_by(|a, b| a.path.cmp(&b.path));
+        let data = serde_json::to_string(&list)?;
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, data)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    fn load(&mut self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else { return Ok(()); };
+        if !path.exists() { return Ok(()); }
+        let data = fs::read_to_string(path)?;
+        let list: Vec<IndexEntry> = serde_json::from_str(&data).unwrap_or_default();
+        self.entries = list.into_iter().map(|e| (e.path.clone(), e)).collect();
+        Ok(())
+    }
+}
+
+fn modified_millis(path: &Path) -> i64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn created_millis(path: &Path) -> Option<i64> {
+    fs::metadata(path)
+        .and_then(|m| m.created())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+}
+
+fn added_at_for(path: &Path, key: &str, mtime: i64, download_times: &HashMap<String, i64>) -> i64 {
+    download_times
+        .get(key)
+        .copied()
+        .or_else(|| created_millis(path))
+        .unwrap_or(mtime)
+}
+
+/// 由下载历史得到各保存路径（及导出的 CBZ）的完成时间，用作入库时间
+pub fn download_times_from_history(records: &[crate::history::DownloadTaskDTO]) -> HashMap<String, i64> {
+    let mut times = HashMap::new();
+    for record in records {
+        if record.save_path.is_empty() || !matches!(record.status.as_str(), "completed" | "partial_failed") {
+            continue;
+        }
+        let Ok(time) = chrono::DateTime::parse_from_rfc3339(&record.complete_time) else { continue; };
+        let millis = time.timestamp_millis();
+        let cbz = crate::export::cbz_path_for(Path::new(&record.save_path));
+        for key in [record.save_path.clone(), cbz.to_string_lossy().to_string()] {
+            // 同一路径多次下载时取最早的一次
+            times.entry(key).and_modify(|t: &mut i64| *t = (*t).min(millis)).or_insert(millis);
+        }
+    }
+    times
+}
+
+fn build_entry(
+    mgr: &Manager,
+    path: &Path,
+    kind: MangaKind,
+    root: &str,
+    mtime: i64,
+) -> anyhow::Result<Option<IndexEntry>> {
+    let Some(manga) = mgr.manga_at(path, kind)? else { return Ok(None); };
+    let size = mgr.manga_size(&manga);
+    let comic_info = match kind {
+        MangaKind::Folder => fs::read_to_string(path.join(COMIC_INFO_FILE)).ok(),
+        MangaKind::Archive => archive::read_entry(path, COMIC_INFO_FILE)
+            .ok()
+            .map(|bytes| String::from_utf8_lossy(&bytes).to_string()),
+    };
+    let xml = comic_info.unwrap_or_default();
+    let list = |name: &str| -> Vec<String> {
+        comic_info::read_element(&xml, name)
+            .map(|t| t.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default()
+    };
+    // 作者名本身可能含逗号（如 `Doe, John`），无法可靠拆分，整体保存，搜索时按子串匹配
+    let writer: Vec<String> = comic_info::read_element(&xml, "Writer")
+        .map(|w| w.trim().to_string())
+        .filter(|w| !w.is_empty())
+        .into_iter()
+        .collect();
+
+    Ok(Some(IndexEntry {
+        path: manga.path,
+        name: manga.name,
+        kind,
+        library: root.to_string(),
+        preview_img: manga.preview_img,
+        images_count: manga.images_count,
+        size,
+        mtime,
+        added_at: 0,
+        source_url: comic_info::read_element(&xml, "Web"),
+        tags: list("Tags"),
+        artists: writer,
+        title: comic_info::read_element(&xml, "Title"),
+        indexed_at: chrono::Utc::now().to_rfc3339(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refresh_reuses_unchanged_entries_and_drops_removed_ones() {
+        let root = std::env::temp_dir().join(format!(
+            "hmanga-index-test-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        let a = root.join("a");
+        let b = root.join("b");
+        fs::create_dir_all(&a).unwrap();
+        fs::create_dir_all(&b).unwrap();
+        fs::write(a.join("1.jpg"), b"xx").unwrap();
+        fs::write(b.join("1.jpg"), b"x").unwrap();
+        fs::write(
+            a.join(COMIC_INFO_FILE),
+            "<ComicInfo>\n  <Writer>Doe, John</Writer>\n  <Tags>female:glasses, full color</Tags>\n  <Web>https://example.test/g/1</Web>\n</ComicInfo>",
+        )
+        .unwrap();
+        let roots = vec![root.to_string_lossy().to_string()];
+
+        let mut index = LibraryIndex::default();
+        index.set_dir(root.join("data"));
+        let no_history = HashMap::new();
+        let first = index.refresh(&roots, false, &no_history);
+        let second = index.refresh(&roots, false, &no_history);
+        fs::remove_dir_all(&b).unwrap();
+        let third = index.refresh(&roots, false, &no_history);
+        // 旧版本索引中的条目没有入库时间，刷新时按下载历史补齐
+        index.entries.values_mut().for_each(|e| e.added_at = 0);
+        let downloaded_at = 1_700_000_000_000;
+        let history = HashMap::from([(a.to_string_lossy().to_string(), downloaded_at)]);
+        let fourth = index.refresh(&roots, false, &history);
+        let reloaded = {
+            let mut idx = LibraryIndex::default();
+            idx.set_dir(root.join("data"));
+            idx.entries(&roots)
+        };
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!((first.added, first.total), (2, 2));
+        assert_eq!((second.unchanged, second.added, second.updated), (2, 0, 0));
+        assert_eq!((third.removed, third.total), (1, 1));
+        assert_eq!((fourth.updated, fourth.unchanged), (1, 0));
+        assert_eq!(reloaded.len(), 1);
+        assert_eq!(reloaded[0].size, 2);
+        assert_eq!(reloaded[0].added_at, downloaded_at);
+        assert_eq!(reloaded[0].artists, vec!["Doe, John"]);
+        assert_eq!(reloaded[0].tags, vec!["female:glasses", "full color"]);
+        assert_eq!(reloaded[0].source_url.as_deref(), Some("https://example.test/g/1"));
+    }
+}
@@ -1,11 +1,28 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::{Path};
+use std::path::{Path, PathBuf};
+
+pub mod archive;
+pub mod index;
+pub mod phash;
+pub mod search;
+
+/// 扫描书库时的最大目录深度
+const MAX_SCAN_DEPTH: usize = 8;
+
+/// 漫画的存储形式
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum MangaKind {
+    Folder,
+    Archive,
+}
 
 #[derive(Clone, Serialize)]
 pub struct Manga {
     pub name: String,
     pub path: String,
+    pub kind: MangaKind,
     pub preview_img: String,
     pub images_count: usize,
     #[serde(skip_serializing_if = "Vec::is_empty")] 
@@ -16,30 +33,60 @@ pub struct Manga {
 pub struct Manager {}
 
 impl Manager {
+    /// 递归扫描书库：含图片的目录与 .cbz/.zip 压缩包均视为一部漫画
     pub fn load_library(&self, root: &str) -> anyhow::Result<Vec<Manga>> {
         let mut mangas: Vec<Manga> = vec![];
-        let root_path = Path::new(root);
-        if !root_path.exists() { return Ok(mangas); }
-        for entry in fs::read_dir(root_path)? {
-            let entry = entry?;
-            if entry.file_type()?.is_dir() {
-                let dir = entry.path();
-                let images = self.images_in_dir(&dir)?;
-                if images.is_empty() { continue; }
-                let mut sorted = images.clone();
-                self.sort_images(&mut sorted);
-                mangas.push(Manga {
-                    name: dir.file_name().unwrap_or_default().to_string_lossy().to_string(),
-                    path: dir.to_string_lossy().to_string(),
-                    preview_img: sorted[0].clone(),
-                    images_count: sorted.len(),
-                    images: vec![],
-                });
+        for (path, kind) in self.discover(root) {
+            // 单个损坏的压缩包或无权限目录不影响整个书库
+            match self.manga_at(&path, kind) {
+                Ok(Some(mut manga)) => {
+                    manga.images.clear();
+                    mangas.push(manga);
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!(path = %path.display(), error = %e, "skip unreadable manga"),
             }
         }
         Ok(mangas)
     }
 
+    /// 列出书库中可能是漫画的目录与压缩包（不读取内容）
+    pub fn discover(&self, root: &str) -> Vec<(PathBuf, MangaKind)> {
+        let root_path = Path::new(root);
+        if !root_path.exists() { return vec![]; }
+        walkdir::WalkDir::new(root_path)
+            .min_depth(1)
+            .max_depth(MAX_SCAN_DEPTH)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter_map(|entry| {
+                let path = entry.path();
+                if entry.file_type().is_dir() {
+                    Some((path.to_path_buf(), MangaKind::Folder))
+                } else if entry.file_type().is_file() && archive::is_archive_file(path) {
+                    Some((path.to_path_buf(), MangaKind::Archive))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// 读取单部漫画，没有图片时返回 None
+    pub fn manga_at(&self, path: &Path, kind: MangaKind) -> anyhow::Result<Option<Manga>> {
+        let images = self.get_manga_images(&path.to_string_lossy())?;
+        if images.is_empty() { return Ok(None); }
+        Ok(Some(Manga {
+            name: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+            path: path.to_string_lossy().to_string(),
+            kind,
+            preview_img: images[0].clone(),
+            images_count: images.len(),
+            images,
+        }))
+    }
+
     pub fn images_in_dir(&self, dir: &Path) -> anyhow::Result<Vec<String>> {
         let mut images = vec![];
         for entry in fs::read_dir(dir)? {
@@ -54,8 +101,35 @@ impl Manager {
         Ok(images)
     }
 
-    pub fn get_manga_images(&self, dir: &str) -> anyhow::Result<Vec<String>> {
-        let mut images = self.images_in_dir(Path::new(dir))?;
+    /// 漫画占用空间（字节）：目录为图片大小之和，压缩包为文件大小
+    pub fn manga_size(&self, manga: &Manga) -> u64 {
+        match manga.kind {
+            MangaKind::Folder => manga
+                .images
+                .iter()
+                .filter_map(|p| fs::metadata(p).ok())
+                .map(|m| m.len())
+                .sum(),
+            MangaKind::Archive => fs::metadata(&manga.path).map(|m| m.len()).unwrap_or(0),
+        }
+    }
+
+    /// 列出压缩包中的页面，返回 `压缩包路径!/条目名` 形式的页面引用
+    pub fn images_in_archive(&self, archive: &Path) -> anyhow::Result<Vec<String>> {
+        Ok(archive::image_entries(archive)?
+            .iter()
+            .map(|entry| archive::page_ref(archive, entry))
+            .collect())
+    }
+
+    /// 获取漫画页面（目录或压缩包），按页码排序
+    pub fn get_manga_images(&self, path: &str) -> anyhow::Result<Vec<String>> {
+        let p = Path::new(path);
+        let mut images = if p.is_file() && archive::is_archive_file(p) {
+            self.images_in_archive(p)?
+        } else {
+            self.images_in_dir(p)?
+        };
         self.sort_images(&mut images);
         Ok(images)
     }
@@ -70,8 +144,11 @@ impl Manager {
 
 
     pub fn delete_manga(&self, path: &str) -> anyhow::Result<bool> {
-        if Path::new(path).exists() {
-            std::fs::remove_dir_all(path)?;
+        let p = Path::new(path);
+        if p.is_file() {
+            std::fs::remove_file(p)?;
+        } else if p.exists() {
+            std::fs::remove_dir_all(p)?;
         }
         Ok(true)
     }
@@ -79,7 +156,7 @@ impl Manager {
 
 fn is_image_file(p: &Path) -> bool {
     match p.extension().and_then(|s| s.to_str()).map(|s| s.to_ascii_lowercase()) {
-        Some(ext) => matches!(ext.as_str(), "jpg"|"jpeg"|"png"|"gif"|"webp"|"bmp"),
+        Some(ext) => matches!(ext.as_str(), "jpg"|"jpeg"|"png"|"gif"|"webp"|"bmp"|"avif"),
         None => false,
     }
 }
@@ -99,4 +176,38 @@ fn page_offset_key(p: &str) -> (i64, i64, String) {
     (i64::MAX, i64::MAX, name)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
 
+    #[test]
+    fn load_library_finds_nested_folders_and_archives() {
+        let root = std::env::temp_dir().join(format!(
+            "hmanga-library-test-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        let nested = root.join("artist").join("gallery");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join("1.jpg"), b"x").unwrap();
+        {
+            let mut zip = zip::ZipWriter::new(fs::File::create(root.join("book.cbz")).unwrap());
+            let options = zip::write::SimpleFileOptions::default();
+            for name in ["10.jpg", "2.jpg"] {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(b"x").unwrap();
+            }
+            zip.finish().unwrap();
+        }
+
+        let mgr = Manager::default();
+        let mangas = mgr.load_library(&root.to_string_lossy()).unwrap();
+        let pages = mgr.get_manga_images(&root.join("book.cbz").to_string_lossy()).unwrap();
+        let _ = fs::remove_dir_all(&root);
+
+        let names: Vec<(&str, MangaKind)> = mangas.iter().map(|m| (m.name.as_str(), m.kind)).collect();
+        assert_eq!(names, vec![("gallery", MangaKind::Folder), ("book.cbz", MangaKind::Archive)]);
+        assert!(pages[0].ends_with("book.cbz!/2.jpg"));
+        assert!(pages[1].ends_with("book.cbz!/10.jpg"));
+    }
+}
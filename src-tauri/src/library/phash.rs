@@ -0,0 +1,234 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+use super::{archive, Manga, MangaKind, Manager};
+
+/// 每部漫画除封面外采样的页数
+const SAMPLE_PAGES: usize = 4;
+/// 默认汉明距离阈值（64 位 dHash）
+pub const DEFAULT_THRESHOLD: u32 = 10;
+
+/// 一部漫画的感知哈希签名
+#[derive(Debug, Clone)]
+pub struct Signature {
+    pub cover: u64,
+    pub pages: Vec<u64>,
+}
+
+/// 重复组中的一部漫画
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateItem {
+    pub name: String,
+    pub path: String,
+    pub kind: MangaKind,
+    pub preview_img: String,
+    pub images_count: usize,
+    pub size: u64,
+}
+
+/// 一组疑似重复的漫画，按占用空间从大到小排列
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    pub items: Vec<DuplicateItem>,
+}
+
+/// 计算图片的 dHash：缩放为 9x8 灰度图，比较相邻像素亮度
+pub fn dhash(bytes: &[u8]) -> anyhow::Result<u64> {
+    let img = image::load_from_memory(bytes)?
+        .resize_exact(9, 8, image::imageops::FilterType::Triangle)
+        .to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let left = img.get_pixel(x, y)[0];
+            let right = img.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | u64::from(left > right);
+        }
+    }
+    Ok(hash)
+}
+
+pub fn hamming(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// 均匀选取采样页（不含封面）
+fn sample_indices(count: usize) -> Vec<usize> {
+    if count <= 1 {
+        return vec![];
+    }
+    let rest = count - 1;
+    let n = SAMPLE_PAGES.min(rest);
+    let mut indices: Vec<usize> = (0..n).map(|i| 1 + (i * rest + rest / 2) / n).collect();
+    indices.dedup();
+    indices
+}
+
+/// 计算漫画签名，封面无法解码时返回 None
+pub fn signature(images: &[String]) -> Option<Signature> {
+    let hash_of = |page: &String| archive::read_page(page).ok().and_then(|b| dhash(&b).ok());
+    let cover = hash_of(images.first()?)?;
+    let pages = sample_indices(images.len())
+        .into_iter()
+        .filter_map(|i| hash_of(&images[i]))
+        .collect();
+    Some(Signature { cover, pages })
+}
+
+/// 封面相近，且采样页中至少一半能在对方找到相近页时视为重复
+pub fn is_similar(a: &Signature, b: &Signature, threshold: u32) -> bool {
+    if hamming(a.cover, b.cover) > threshold {
+        return false;
+    }
+    let (small, large) = if a.pages.len() <= b.pages.len() { (a, b) } else { (b, a) };
+    if small.pages.is_empty() {
+        return true;
+    }
+    let matched = small
+        .pages
+        .iter()
+        .filter(|p| large.pages.iter().any(|q| hamming(**p, *q) <= threshold))
+        .count();
+    matched * 2 >= small.pages.len()
+}
+
+/// 将相似签名聚类（并查集），只返回包含两项及以上的组，元素为输入下标
+pub fn cluster(signatures: &[Signature], threshold: u32) -> Vec<Vec<usize>> {
+    let mut parent: Vec<usize> = (0..signatures.len()).collect();
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for i in 0..signatures.len() {
+        for j in (i + 1)..signatures.len() {
+            if is_similar(&signatures[i], &signatures[j], threshold) {
+                let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
+                if ri != rj {
+                    parent[rj] = ri;
+                }
+            }
+        }
+    }
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..signatures.len() {
+        let root = find(&mut parent, i);
+        groups.entry(root).or_default().push(i);
+    }
+    let mut result: Vec<Vec<usize>> = groups.into_values().filter(|g| g.len() > 1).collect();
+    result.sort();
+    result
+}
+
+/// 扫描书库并找出疑似重复的漫画，`on_progress(已处理, 总数)` 用于上报进度
+pub fn find_duplicates(
+    roots: &[String],
+    threshold: u32,
+    on_progress: impl Fn(usize, usize) + Sync,
+) -> Vec<DuplicateGroup> {
+    let mgr = Manager::default();
+    let mangas: Vec<Manga> = roots
+        .iter()
+        .flat_map(|root| mgr.discover(root))
+        .filter_map(|(path, kind)| mgr.manga_at(&path, kind).ok().flatten())
+        .collect();
+    let total = mangas.len();
+    let done = std::sync::atomic::AtomicUsize::new(0);
+
+    // 解码图片较慢，按 CPU 数分块并行计算
+    let workers = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+    let chunk = total.div_ceil(workers).max(1);
+    let hashed: Vec<(usize, Signature)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = mangas
+            .chunks(chunk)
+            .enumerate()
+            .map(|(c, part)| {
+                let (done, on_progress) = (&done, &on_progress);
+                scope.spawn(move || {
+                    part.iter()
+                        .enumerate()
+                        .filter_map(|(i, manga)| {
+                            let sig = signature(&manga.images);
+                            let n = done.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
+                            on_progress(n, total);
+                            sig.map(|s| (c * chunk + i, s))
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles.into_iter().flat_map(|h| h.join().unwrap_or_default()).collect()
+    });
+
+    let signatures: Vec<Signature> = hashed.iter().map(|(_, s)| s.clone()).collect();
+    cluster(&signatures, threshold)
+        .into_iter()
+        .map(|group| {
+            let mut items: Vec<DuplicateItem> = group
+                .into_iter()
+                .map(|i| {
+                    let manga = &mangas[hashed[i].0];
+                    DuplicateItem {
+                        name: manga.name.clone(),
+                        path: manga.path.clone(),
+                        kind: manga.kind,
+                        preview_img: manga.preview_img.clone(),
+                        images_count: manga.images_count,
+                        size: mgr.manga_size(manga),
+                    }
+                })
+                .collect();
+            items.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+            DuplicateGroup { items }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient_png(width: u32, height: u32, invert: bool) -> Vec<u8> {
+        let img = image::GrayImage::from_fn(width, height, |x, _| {
+            let v = (x * 255 / width) as u8;
+            image::Luma([if invert { 255 - v } else { v }])
+        });
+        let mut buf = std::io::Cursor::new(Vec::new());
+        img.write_to(&mut buf, image::ImageFormat::Png).unwrap();
+        buf.into_inner()
+    }
+
+    #[test]
+    fn dhash_is_stable_across_resolutions() {
+        let small = dhash(&gradient_png(90, 80, false)).unwrap();
+        let large = dhash(&gradient_png(900, 800, false)).unwrap();
+        let inverted = dhash(&gradient_png(90, 80, true)).unwrap();
+
+        assert!(hamming(small, large) <= 2);
+        assert!(hamming(small, inverted) > DEFAULT_THRESHOLD);
+    }
+
+    #[test]
+    fn clusters_similar_signatures_transitively() {
+        let sig = |cover: u64, pages: &[u64]| Signature { cover, pages: pages.to_vec() };
+        let signatures = vec![
+            sig(0, &[0xFF, 0xFF00]),
+            sig(u64::MAX, &[0]),
+            sig(0b11, &[0xFF, 0xFF00, 0xF0F0_F0F0]),
+            sig(0b111, &[0xFF]),
+        ];
+
+        assert_eq!(cluster(&signatures, 4), vec![vec![0, 2, 3]]);
+    }
+
+    #[test]
+    fn samples_pages_evenly_excluding_cover() {
+        assert!(sample_indices(1).is_empty());
+        assert_eq!(sample_indices(3), vec![1, 2]);
+        assert_eq!(sample_indices(101), vec![13, 38, 63, 88]);
+    }
+}
@@ -0,0 +1,210 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+use super::index::IndexEntry;
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SortField {
+    Name,
+    #[default]
+    AddedAt,
+    PageCount,
+    Size,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// 书库搜索条件，所有条件之间为“与”关系
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchQuery {
+    /// 标题关键字，匹配目录名和 ComicInfo 标题（不区分大小写）
+    pub keyword: Option<String>,
+    /// 需同时包含的标签，`glasses` 可匹配 `female:glasses`
+    pub tags: Vec<String>,
+    pub artist: Option<String>,
+    /// 来源站点，按来源 URL 的域名匹配，例如 `e-hentai`
+    pub site: Option<String>,
+    pub min_pages: Option<usize>,
+    pub max_pages: Option<usize>,
+    /// 入库时间范围，支持 RFC 3339 或 `YYYY-MM-DD`
+    pub added_after: Option<String>,
+    pub added_before: Option<String>,
+    pub sort_by: SortField,
+    pub sort_order: SortOrder,
+    /// 页码，从 1 开始
+    pub page: usize,
+    pub page_size: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub items: Vec<IndexEntry>,
+    pub total: usize,
+    pub page: usize,
+    pub page_size: usize,
+}
+
+/// 在索引条目中筛选、排序并分页
+pub fn search(entries: Vec<IndexEntry>, query: &SearchQuery) -> anyhow::Result<SearchResult> {
+    let added_after = query.added_after.as_deref().map(|s| parse_time(s, false)).transpose()?;
+    let added_before = query.added_before.as_deref().map(|s| parse_time(s, true)).transpose()?;
+    let keyword = normalized(query.keyword.as_deref());
+    let artist = normalized(query.artist.as_deref());
+    let site = normalized(query.site.as_deref());
+    let tags: Vec<String> = query
+        .tags
+        .iter()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    let mut matched: Vec<IndexEntry> = entries
+        .into_iter()
+        .filter(|e| {
+            keyword.as_deref().is_none_or(|k| {
+                e.name.to_lowercase().contains(k)
+                    || e.title.as_deref().is_some_and(|t| t.to_lowercase().contains(k))
+            })
+        })
+        .filter(|e| tags.iter().all(|t| e.tags.iter().any(|et| tag_matches(et, t))))
+        .filter(|e| {
+            artist
+                .as_deref()
+                .is_none_or(|a| e.artists.iter().any(|ea| ea.to_lowercase().contains(a)))
+        })
+        .filter(|e| site.as_deref().is_none_or(|s| source_host(e).is_some_and(|h| h.contains(s))))
+        .filter(|e| query.min_pages.is_none_or(|n| e.images_count >= n))
+        .filter(|e| query.max_pages.is_none_or(|n| e.images_count <= n))
+        .filter(|e| added_after.is_none_or(|t| e.added_at >= t))
+        .filter(|e| added_before.is_none_or(|t| e.added_at <= t))
+        .collect();
+
+    matched.sort_by(|a, b| {
+        let ord = match query.sort_by {
+            SortField::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            SortField::AddedAt => a.added_at.cmp(&b.added_at),
+            SortField::PageCount => a.images_count.cmp(&b.images_count),
+            SortField::Size => a.size.cmp(&b.size),
+        };
+        let ord = if ord == Ordering::Equal { a.path.cmp(&b.path) } else { ord };
+        match query.sort_order {
+            SortOrder::Asc => ord,
+            SortOrder::Desc => ord.reverse(),
+        }
+    });
+
+    let page = query.page.max(1);
+    let page_size = match query.page_size {
+        0 => DEFAULT_PAGE_SIZE,
+        n => n.min(MAX_PAGE_SIZE),
+    };
+    let total = matched.len();
+    let items = matched.into_iter().skip((page - 1) * page_size).take(page_size).collect();
+    Ok(SearchResult { items, total, page, page_size })
+}
+
+fn normalized(value: Option<&str>) -> Option<String> {
+    value.map(|v| v.trim().to_lowercase()).filter(|v| !v.is_empty())
+}
+
+/// 标签完全匹配，或忽略命名空间后匹配（`glasses` 匹配 `female:glasses`）
+fn tag_matches(tag: &str, wanted: &str) -> bool {
+    let tag = tag.to_lowercase();
+    if tag == wanted {
+        return true;
+    }
+    !wanted.contains(':') && tag.rsplit(':').next() == Some(wanted)
+}
+
+fn source_host(entry: &IndexEntry) -> Option<String> {
+    let url = url::Url::parse(entry.source_url.as_deref()?).ok()?;
+    url.host_str().map(|h| h.to_lowercase())
+}
+
+/// 解析为毫秒时间戳；仅给出日期时，`end_of_day` 决定取当天开始还是结束
+fn parse_time(value: &str, end_of_day: bool) -> anyhow::Result<i64> {
+    let value = value.trim();
+    if let Ok(t) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(t.timestamp_millis());
+    }
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("无效的日期: {}", value))?;
+    let time = if end_of_day {
+        date.and_hms_milli_opt(23, 59, 59, 999)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    };
+    Ok(time.map(|t| t.and_utc().timestamp_millis()).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::MangaKind;
+
+    fn entry(name: &str, pages: usize, added_at: i64, tags: &[&str], source: &str) -> IndexEntry {
+        IndexEntry {
+            path: format!("/lib/{}", name),
+            name: name.to_string(),
+            kind: MangaKind::Folder,
+            library: "/lib".to_string(),
+            preview_img: String::new(),
+            images_count: pages,
+            size: 0,
+            mtime: added_at,
+            added_at,
+            source_url: Some(source.to_string()),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            artists: vec!["Some Artist".to_string()],
+            title: None,
+            indexed_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn filters_by_tag_site_and_pages_then_sorts_and_paginates() {
+        let entries = vec![
+            entry("Alpha", 20, 3, &["female:glasses"], "https://e-hentai.org/g/1/a/"),
+            entry("Beta", 40, 2, &["female:glasses", "full color"], "https://e-hentai.org/g/2/b/"),
+            entry("Gamma", 30, 1, &["female:glasses"], "https://nhentai.net/g/3/"),
+            entry("Delta", 5, 4, &["female:glasses"], "https://e-hentai.org/g/4/d/"),
+        ];
+        let query = SearchQuery {
+            tags: vec!["Glasses".to_string()],
+            site: Some("e-hentai".to_string()),
+            min_pages: Some(10),
+            sort_by: SortField::PageCount,
+            sort_order: SortOrder::Desc,
+            page: 2,
+            page_size: 1,
+            ..SearchQuery::default()
+        };
+
+        let result = search(entries, &query).unwrap();
+
+        assert_eq!(result.total, 2);
+        assert_eq!(result.items.len(), 1);
+        assert_eq!(result.items[0].name, "Alpha");
+    }
+
+    #[test]
+    fn date_only_bounds_cover_whole_day() {
+        let start = parse_time("2026-04-18", false).unwrap();
+        let end = parse_time("2026-04-18", true).unwrap();
+
+        assert_eq!(end - start, 24 * 3600 * 1000 - 1);
+        assert!(parse_time("yesterday", false).is_err());
+    }
+}
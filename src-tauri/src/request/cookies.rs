@@ -0,0 +1,637 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use reqwest::header::{HeaderMap, HeaderValue, COOKIE};
+use serde::{Deserialize, Serialize};
+use tauri::Manager as TauriManager;
+use url::Url;
+
+const COOKIES_FILE: &str = "cookies.json";
+
+/// 响应中的 `Set-Cookie` 变化后延迟保存，期间的多次变化合并为一次写入
+const SAVE_DELAY: Duration = Duration::from_secs(2);
+
+/// 持久化的 cookie
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredCookie {
+    /// 不带前导点的域名
+    pub domain: String,
+    /// 为 true 时只发送给 `domain` 本身，不包括子域名
+    #[serde(default)]
+    pub host_only: bool,
+    pub path: String,
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub secure: bool,
+    /// 过期时间（Unix 秒），None 为会话 cookie
+    #[serde(default)]
+    pub expires: Option<i64>,
+}
+
+impl StoredCookie {
+    fn is_expired(&self, now: i64) -> bool {
+        self.expires.is_some_and(|e| e <= now)
+    }
+
+    fn matches(&self, url: &Url, now: i64) -> bool {
+        let Some(host) = url.host_str().map(|h| h.to_ascii_lowercase()) else { return false; };
+        let domain_ok = host == self.domain || (!self.host_only && host.ends_with(&format!(".{}", self.domain)));
+        domain_ok
+            && path_matches(&self.path, url.path())
+            && (!self.secure || url.scheme() == "https")
+            && !self.is_expired(now)
+    }
+
+    fn same_key(&self, other: &StoredCookie) -> bool {
+        self.domain == other.domain && self.path == other.path && self.name == other.name
+    }
+}
+
+fn path_matches(cookie_path: &str, request_path: &str) -> bool {
+    request_path == cookie_path
+        || (request_path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || request_path[cookie_path.len()..].starts_with('/')))
+}
+
+/// 应用数据目录中的 cookie 存储，作为所有请求客户端的 cookie 来源，
+/// 响应中的 `Set-Cookie` 会在后台延迟写回
+#[derive(Default)]
+pub struct CookieJar {
+    path: Arc<RwLock<Option<PathBuf>>>,
+    cookies: Arc<RwLock<Vec<StoredCookie>>>,
+    /// 已安排延迟保存，尚未写入
+    save_pending: Arc<AtomicBool>,
+}
+
+static JAR: Lazy<Arc<CookieJar>> = Lazy::new(|| Arc::new(CookieJar::default()));
+
+pub fn cookie_jar() -> &'static Arc<CookieJar> {
+    &JAR
+}
+
+impl CookieJar {
+    pub fn set_dir_from_app(&self, app: &tauri::AppHandle) -> anyhow::Result<()> {
+        #[allow(deprecated)]
+        let base = app
+            .path()
+            .app_data_dir()
+            .unwrap_or(std::env::temp_dir());
+        self.set_dir(base)
+    }
+
+    /// 设置存储目录并加载已有 cookie；文件无法解析时改名备份后从空存储开始，并返回错误
+    pub fn set_dir(&self, dir: PathBuf) -> anyhow::Result<()> {
+        *self.path.write() = Some(dir.join(COOKIES_FILE));
+        self.load()
+    }
+
+    /// 发往该地址的 cookie
+    pub fn cookies_for(&self, url: &Url) -> Vec<StoredCookie> {
+        let now = chrono::Utc::now().timestamp();
+        let mut matched: Vec<StoredCookie> = self
+            .cookies
+            .read()
+            .iter()
+            .filter(|c| c.matches(url, now))
+            .cloned()
+            .collect();
+        // 路径更长的优先
+        matched.sort_by_key(|c| std::cmp::Reverse(c.path.len()));
+        matched
+    }
+
+    /// 请求已带 `Cookie` 头（站点配置或固定 cookie）时，补充存储中同名之外的 cookie；
+    /// 未带时由 reqwest 通过 `CookieStore` 自动添加
+    pub fn merge_into(&self, url: &str, headers: &mut HeaderMap) {
+        let Some(existing) = headers.get(COOKIE).and_then(|v| v.to_str().ok()).map(|s| s.to_string()) else {
+            return;
+        };
+        let Ok(url) = Url::parse(url) else { return; };
+        let mut names: HashSet<String> = parse_cookie_pairs(&existing).into_iter().map(|(n, _)| n).collect();
+        let extra: Vec<String> = self
+            .cookies_for(&url)
+            .into_iter()
+            .filter(|c| names.insert(c.name.clone()))
+            .map(|c| format!("{}={}", c.name, c.value))
+            .collect();
+        if extra.is_empty() {
+            return;
+        }
+        if let Ok(value) = HeaderValue::from_str(&format!("{}; {}", existing.trim_end_matches([';', ' ']), extra.join("; "))) {
+            headers.insert(COOKIE, value);
+        }
+    }
+
+    /// 导入 cookie，同名的覆盖，返回导入数量
+    pub fn import(&self, imported: Vec<StoredCookie>) -> anyhow::Result<usize> {
+        let count = imported.len();
+        {
+            let mut cookies = self.cookies.write();
+            for cookie in imported {
+                cookies.retain(|c| !c.same_key(&cookie));
+                cookies.push(cookie);
+            }
+        }
+        self.save()?;
+        Ok(count)
+    }
+
+    /// 删除某个域名（含子域名）下的 cookie，返回删除数量
+    pub fn clear_domain(&self, domain: &str) -> anyhow::Result<usize> {
+        let domain = domain.trim().trim_start_matches('.').to_ascii_lowercase();
+        let removed = {
+            let mut cookies = self.cookies.write();
+            let before = cookies.len();
+            cookies.retain(|c| c.domain != domain && !c.domain.ends_with(&format!(".{}", domain)));
+            before - cookies.len()
+        };
+        if removed > 0 {
+            self.save()?;
+        }
+        Ok(removed)
+    }
+
+    /// 记录响应中的 `Set-Cookie`，返回是否有变化
+    fn store_set_cookies<'a>(&self, headers: impl Iterator<Item = &'a str>, url: &Url) -> bool {
+        let now = chrono::Utc::now().timestamp();
+        let mut changed = false;
+        let mut cookies = self.cookies.write();
+        for header in headers {
+            let Some(cookie) = parse_set_cookie(header, url, now) else { continue; };
+            let existing = cookies.iter().position(|c| c.same_key(&cookie));
+            if cookie.is_expired(now) {
+                // 过期时间在过去表示删除
+                if let Some(i) = existing {
+                    cookies.remove(i);
+                    changed = true;
+                }
+                continue;
+            }
+            match existing {
+                Some(i) if cookies[i] == cookie => {}
+                Some(i) => {
+                    cookies[i] = cookie;
+                    changed = true;
+                }
+                None => {
+                    cookies.push(cookie);
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        self.save_pending.store(false, Ordering::SeqCst);
+        write_cookies(&self.path, &self.cookies)
+    }
+
+    /// 在后台线程中延迟保存，不阻塞发出请求的任务
+    fn schedule_save(&self) {
+        if self.save_pending.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let (path, cookies, pending) = (self.path.clone(), self.cookies.clone(), self.save_pending.clone());
+        std::thread::spawn(move || {
+            std::thread::sleep(SAVE_DELAY);
+            // 期间已由 import / clear_domain 等同步保存时不再重复写入
+            if pending.swap(false, Ordering::SeqCst) {
+                if let Err(e) = write_cookies(&path, &cookies) {
+                    tracing::warn!(error = %e, "failed to save cookies");
+                }
+            }
+        });
+    }
+
+    /// 立即写入尚未保存的变化（应用退出时调用）
+    pub fn flush(&self) -> anyhow::Result<()> {
+        if self.save_pending.load(Ordering::SeqCst) {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    fn load(&self) -> anyhow::Result<()> {
+        let Some(path) = self.path.read().clone() else { return Ok(()); };
+        if !path.exists() { return Ok(()); }
+        let data = fs::read_to_string(&path)?;
+        match serde_json::from_str(&data) {
+            Ok(cookies) => {
+                *self.cookies.write() = cookies;
+                Ok(())
+            }
+            Err(e) => {
+                // 保留损坏的文件，之后的保存不会覆盖它
+                let backup = path.with_extension("json.corrupt");
+                fs::rename(&path, &backup)?;
+                anyhow::bail!("cookie 文件无法解析，已备份为 {}: {}", backup.display(), e)
+            }
+        }
+    }
+}
+
+// 后台延迟保存与导入、清除时的同步保存共用同一个临时文件，写入时互斥
+static WRITE_LOCK: Lazy<parking_lot::Mutex<()>> = Lazy::new(|| parking_lot::Mutex::new(()));
+
+fn write_cookies(path: &RwLock<Option<PathBuf>>, cookies: &RwLock<Vec<StoredCookie>>) -> anyhow::Result<()> {
+    let Some(path) = path.read().clone() else { return Ok(()); };
+    let _guard = WRITE_LOCK.lock();
+    if let Some(p) = path.parent() { fs::create_dir_all(p)?; }
+    let now = chrono::Utc::now().timestamp();
+    // 会话 cookie 也一并保存：导入的 cookies.txt 常不带过期时间，重启后仍需保持登录
+    let alive: Vec<StoredCookie> = cookies
+        .read()
+        .iter()
+        .filter(|c| !c.is_expired(now))
+        .cloned()
+        .collect();
+    let data = serde_json::to_string_pretty(&alive)?;
+    // 先写临时文件再替换，避免写入中途退出导致文件损坏
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, data)?;
+    fs::rename(tmp, path)?;
+    Ok(())
+}
+
+impl reqwest::cookie::CookieStore for CookieJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let headers = cookie_headers.filter_map(|v| v.to_str().ok());
+        if self.store_set_cookies(headers, url) {
+            self.schedule_save();
+        }
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let header = self
+            .cookies_for(url)
+            .iter()
+            .map(|c| format!("{}={}", c.name, c.value))
+            .collect::<Vec<_>>()
+            .join("; ");
+        if header.is_empty() {
+            return None;
+        }
+        HeaderValue::from_str(&header).ok()
+    }
+}
+
+/// 解析 `a=1; b=2` 形式的 cookie 字符串
+pub fn parse_cookie_pairs(value: &str) -> Vec<(String, String)> {
+    value
+        .split(';')
+        .filter_map(|pair| {
+            let (name, value) = pair.split_once('=')?;
+            let name = name.trim();
+            (!name.is_empty()).then(|| (name.to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+fn parse_cookie_date(value: &str) -> Option<i64> {
+    let value = value.trim();
+    if let Ok(date) = chrono::DateTime::parse_from_rfc2822(value) {
+        return Some(date.timestamp());
+    }
+    ["%a, %d-%b-%Y %H:%M:%S GMT", "%a, %d-%b-%y %H:%M:%S GMT", "%A, %d-%b-%y %H:%M:%S GMT"]
+        .iter()
+        .find_map(|fmt| chrono::NaiveDateTime::parse_from_str(value, fmt).ok())
+        .map(|date| date.and_utc().timestamp())
+}
+
+/// 解析一条 `Set-Cookie`，域名不属于请求地址时忽略
+fn parse_set_cookie(header: &str, url: &Url, now: i64) -> Option<StoredCookie> {
+    let host = url.host_str()?.to_ascii_lowercase();
+    let mut parts = header.split(';');
+    let (name, value) = parts.next()?.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    let default_path = match url.path().rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(i) => url.path()[..i].to_string(),
+    };
+    let mut cookie = StoredCookie {
+        domain: host.clone(),
+        host_only: true,
+        path: default_path,
+        name: name.to_string(),
+        value: value.trim().trim_matches('"').to_string(),
+        secure: false,
+        expires: None,
+    };
+    let mut max_age = None;
+    for attr in parts {
+        let (key, val) = attr.split_once('=').unwrap_or((attr, ""));
+        let val = val.trim();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "domain" if !val.is_empty() => {
+                let domain = val.trim_start_matches('.').to_ascii_lowercase();
+                if host != domain && !host.ends_with(&format!(".{}", domain)) {
+                    return None;
+                }
+                cookie.domain = domain;
+                cookie.host_only = false;
+            }
+            "path" if val.starts_with('/') => cookie.path = val.to_string(),
+            "expires" => cookie.expires = parse_cookie_date(val).or(cookie.expires),
+            "max-age" => max_age = val.parse::<i64>().ok(),
+            "secure" => cookie.secure = true,
+            _ => {}
+        }
+    }
+    // Max-Age 优先于 Expires
+    if let Some(age) = max_age {
+        cookie.expires = Some(if age <= 0 { 0 } else { now + age });
+    }
+    Some(cookie)
+}
+
+/// 解析浏览器导出的 cookie：Netscape `cookies.txt` 或 JSON（Cookie-Editor、EditThisCookie 等）
+pub fn parse_cookie_export(content: &str) -> anyhow::Result<Vec<StoredCookie>> {
+    let trimmed = content.trim_start();
+    if trimmed.starts_with('[') || trimmed.starts_with('{') {
+        parse_json_export(trimmed)
+    } else {
+        Ok(parse_netscape(content))
+    }
+}
+
+fn parse_netscape(content: &str) -> Vec<StoredCookie> {
+    content
+        .lines()
+        .filter_map(|line| {
+            // curl 用 `#HttpOnly_` 前缀标记 HttpOnly cookie
+            let line = line.strip_prefix("#HttpOnly_").unwrap_or(line);
+            if line.trim().is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() < 7 {
+                return None;
+            }
+            let expires = fields[4].trim().parse::<i64>().ok().filter(|e| *e > 0);
+            Some(StoredCookie {
+                domain: fields[0].trim().trim_start_matches('.').to_ascii_lowercase(),
+                host_only: fields[1].trim().eq_ignore_ascii_case("FALSE"),
+                path: fields[2].trim().to_string(),
+                secure: fields[3].trim().eq_ignore_ascii_case("TRUE"),
+                expires,
+                name: fields[5].to_string(),
+                value: fields[6].trim_end_matches('\r').to_string(),
+            })
+        })
+        .filter(|c| !c.domain.is_empty() && !c.name.is_empty())
+        .collect()
+}
+
+fn parse_json_export(content: &str) -> anyhow::Result<Vec<StoredCookie>> {
+    let json: serde_json::Value = serde_json::from_str(content)?;
+    let entries = match &json {
+        serde_json::Value::Array(items) => items.clone(),
+        serde_json::Value::Object(obj) => obj
+            .get("cookies")
+            .and_then(|c| c.as_array())
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("JSON 中没有 cookies 数组"))?,
+        _ => anyhow::bail!("无法识别的 cookie 导出格式"),
+    };
+    Ok(entries
+        .iter()
+        .filter_map(|entry| {
+            let text = |key: &str| entry.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+            let flag = |key: &str| entry.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+            let domain = text("domain")?;
+            let session = flag("session");
+            let expires = ["expirationDate", "expires"]
+                .iter()
+                .find_map(|key| entry.get(*key).and_then(|v| v.as_f64()))
+                .filter(|e| !session && *e > 0.0)
+                .map(|e| e as i64);
+            Some(StoredCookie {
+                // 没有 hostOnly 字段时按域名是否带前导点判断
+                host_only: entry.get("hostOnly").and_then(|v| v.as_bool()).unwrap_or(!domain.starts_with('.')),
+                domain: domain.trim_start_matches('.').to_ascii_lowercase(),
+                path: text("path").unwrap_or_else(|| "/".to_string()),
+                name: text("name")?,
+                value: text("value").unwrap_or_default(),
+                secure: flag("secure"),
+                expires,
+            })
+        })
+        .filter(|c| !c.domain.is_empty() && !c.name.is_empty())
+        .collect())
+}
+
+/// 需要登录的站点及判断登录状态的 cookie
+struct SiteLogin {
+    site: &'static str,
+    /// 对应的解析器配置名称（`AuthConfig.cookies`）
+    config_key: &'static str,
+    url: &'static str,
+    required: &'static [&'static str],
+}
+
+const SITE_LOGINS: &[SiteLogin] = &[
+    SiteLogin { site: "ehentai", config_key: "ehentai", url: "https://e-hentai.org/", required: &["ipb_member_id", "ipb_pass_hash"] },
+    SiteLogin { site: "exhentai", config_key: "ehentai", url: "https://exhentai.org/", required: &["ipb_member_id", "ipb_pass_hash", "igneous"] },
+    SiteLogin { site: "pixiv", config_key: "pixiv", url: "https://www.pixiv.net/", required: &["PHPSESSID"] },
+    SiteLogin { site: "nhentai", config_key: "nhentai", url: "https://nhentai.net/", required: &["sessionid"] },
+];
+
+/// 游客也会拿到部分同名 cookie，按取值判断是否为登录后的会话
+fn looks_logged_in(name: &str, value: &str) -> bool {
+    match name {
+        "ipb_member_id" => !value.is_empty() && value != "0",
+        "igneous" => !value.is_empty() && value != "mystery",
+        // 登录后的 PHPSESSID 形如 `<用户ID>_<随机串>`
+        "PHPSESSID" => value.split_once('_').is_some_and(|(uid, _)| !uid.is_empty() && uid.chars().all(|c| c.is_ascii_digit())),
+        _ => !value.is_empty(),
+    }
+}
+
+/// 站点的登录状态
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionStatus {
+    pub site: String,
+    pub logged_in: bool,
+    /// cookie 来源：`config`（站点配置）或 `jar`（导入或自动保存）
+    pub source: Option<String>,
+    /// 缺少或无效的登录 cookie
+    pub missing: Vec<String>,
+    /// 登录 cookie 中最早的过期时间（RFC 3339）
+    pub expires_at: Option<String>,
+}
+
+fn evaluate_session(login: &SiteLogin, config_cookies: &[(String, String)], jar_cookies: &[StoredCookie]) -> SessionStatus {
+    let mut missing = Vec::new();
+    let mut sources = HashSet::new();
+    let mut expires: Option<i64> = None;
+    for name in login.required {
+        // 与请求时一致：站点配置中的 cookie 优先
+        if let Some((_, value)) = config_cookies.iter().find(|(n, _)| n == name) {
+            if looks_logged_in(name, value) {
+                sources.insert("config");
+                continue;
+            }
+        } else if let Some(cookie) = jar_cookies.iter().find(|c| c.name == *name) {
+            if looks_logged_in(name, &cookie.value) {
+                sources.insert("jar");
+                if let Some(e) = cookie.expires {
+                    expires = Some(expires.map_or(e, |cur| cur.min(e)));
+                }
+                continue;
+            }
+        }
+        missing.push(name.to_string());
+    }
+    let source = match (sources.contains("config"), sources.contains("jar")) {
+        (true, true) => Some("config+jar".to_string()),
+        (true, false) => Some("config".to_string()),
+        (false, true) => Some("jar".to_string()),
+        (false, false) => None,
+    };
+    SessionStatus {
+        site: login.site.to_string(),
+        logged_in: missing.is_empty(),
+        source,
+        missing,
+        expires_at: expires
+            .and_then(|e| chrono::DateTime::from_timestamp(e, 0))
+            .map(|d| d.to_rfc3339()),
+    }
+}
+
+/// 各站点的登录状态，`config_cookies` 返回站点配置中的 cookie 字符串
+pub fn session_statuses(config_cookies: impl Fn(&str) -> Option<String>) -> Vec<SessionStatus> {
+    let jar = cookie_jar();
+    SITE_LOGINS
+        .iter()
+        .map(|login| {
+            let configured = config_cookies(login.config_key)
+                .map(|c| parse_cookie_pairs(&c))
+                .unwrap_or_default();
+            let jar_cookies = Url::parse(login.url).map(|u| jar.cookies_for(&u)).unwrap_or_default();
+            evaluate_session(login, &configured, &jar_cookies)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::cookie::CookieStore;
+
+    #[test]
+    fn parses_netscape_and_json_exports() {
+        let netscape = "# Netscape HTTP Cookie File\n\
+            .e-hentai.org\tTRUE\t/\tFALSE\t1999999999\tipb_member_id\t123\n\
+            #HttpOnly_.e-hentai.org\tTRUE\t/\tTRUE\t1999999999\tipb_pass_hash\tabc\n\
+            nhentai.net\tFALSE\t/\tTRUE\t0\tcsrftoken\tx\n";
+        let cookies = parse_cookie_export(netscape).unwrap();
+        assert_eq!(cookies.len(), 3);
+        assert_eq!(cookies[1].name, "ipb_pass_hash");
+        assert!(cookies[1].secure && !cookies[1].host_only);
+        assert!(cookies[2].host_only);
+        assert_eq!(cookies[2].expires, None);
+
+        let json = r#"[{"domain": ".pixiv.net", "name": "PHPSESSID", "value": "1_x", "path": "/",
+            "secure": true, "hostOnly": false, "session": false, "expirationDate": 1999999999.5}]"#;
+        let cookies = parse_cookie_export(json).unwrap();
+        assert_eq!(cookies[0].domain, "pixiv.net");
+        assert_eq!(cookies[0].expires, Some(1999999999));
+    }
+
+    #[test]
+    fn captures_set_cookie_and_sends_to_matching_hosts() {
+        let jar = CookieJar::default();
+        let url = Url::parse("https://forums.e-hentai.org/index.php?act=Login").unwrap();
+        let headers = [
+            HeaderValue::from_static("ipb_member_id=123; expires=Wed, 01-Jan-2099 00:00:00 GMT; path=/; domain=.e-hentai.org"),
+            HeaderValue::from_static("session_id=s; path=/"),
+            HeaderValue::from_static("evil=1; domain=.pixiv.net"),
+        ];
+        jar.set_cookies(&mut headers.iter(), &url);
+
+        let cookie = |u: &str| jar.cookies(&Url::parse(u).unwrap()).map(|v| v.to_str().unwrap().to_string());
+        assert_eq!(cookie("https://e-hentai.org/g/1/abc/"), Some("ipb_member_id=123".to_string()));
+        assert_eq!(cookie("https://forums.e-hentai.org/"), Some("ipb_member_id=123; session_id=s".to_string()));
+        assert_eq!(cookie("https://www.pixiv.net/"), None);
+
+        // 过期的 Set-Cookie 删除已有 cookie
+        let delete = [HeaderValue::from_static("ipb_member_id=; max-age=0; path=/; domain=.e-hentai.org")];
+        jar.set_cookies(&mut delete.iter(), &url);
+        assert_eq!(cookie("https://e-hentai.org/"), None);
+    }
+
+    #[test]
+    fn merges_jar_cookies_into_explicit_header() {
+        let jar = CookieJar::default();
+        jar.import(parse_netscape(".e-hentai.org\tTRUE\t/\tFALSE\t1999999999\tipb_member_id\t123\n\
+            .e-hentai.org\tTRUE\t/\tFALSE\t1999999999\tnw\t0\n")).unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(COOKIE, HeaderValue::from_static("nw=1"));
+
+        jar.merge_into("https://e-hentai.org/g/1/abc/", &mut headers);
+
+        assert_eq!(headers.get(COOKIE).unwrap(), "nw=1; ipb_member_id=123");
+    }
+
+    #[test]
+    fn reports_session_status_from_config_and_jar() {
+        let exhentai = &SITE_LOGINS[1];
+        let jar_cookies = parse_netscape(".exhentai.org\tTRUE\t/\tFALSE\t1999999999\tigneous\tmystery\n");
+        let config = parse_cookie_pairs("ipb_member_id=123; ipb_pass_hash=abc");
+
+        let status = evaluate_session(exhentai, &config, &jar_cookies);
+        assert!(!status.logged_in);
+        assert_eq!(status.missing, vec!["igneous"]);
+        assert_eq!(status.source.as_deref(), Some("config"));
+
+        let pixiv = &SITE_LOGINS[2];
+        let jar_cookies = parse_netscape(".pixiv.net\tTRUE\t/\tTRUE\t1999999999\tPHPSESSID\t42_abc\n");
+        let status = evaluate_session(pixiv, &[], &jar_cookies);
+        assert!(status.logged_in);
+        assert_eq!(status.source.as_deref(), Some("jar"));
+        assert!(status.expires_at.is_some());
+    }
+
+    #[test]
+    fn set_cookie_is_saved_later_and_corrupt_file_is_kept() {
+        let dir = std::env::temp_dir().join(format!(
+            "hmanga-cookies-test-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(COOKIES_FILE), "not json").unwrap();
+
+        let jar = CookieJar::default();
+        let loaded = jar.set_dir(dir.clone());
+        let backup = fs::read_to_string(dir.join("cookies.json.corrupt"));
+
+        let url = Url::parse("https://e-hentai.org/").unwrap();
+        jar.set_cookies(&mut [HeaderValue::from_static("nw=1; path=/")].iter(), &url);
+        let written_immediately = dir.join(COOKIES_FILE).exists();
+        jar.flush().unwrap();
+        let reloaded = CookieJar::default();
+        let reload = reloaded.set_dir(dir.clone());
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(loaded.is_err());
+        assert_eq!(backup.unwrap(), "not json");
+        assert!(!written_immediately);
+        assert!(reload.is_ok());
+        assert_eq!(reloaded.cookies.read().len(), 1);
+    }
+}
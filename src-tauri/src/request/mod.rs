@@ -1,7 +1,16 @@
-use reqwest::{Client as ReqwestClient, ClientBuilder, header::HeaderMap, Proxy, Response};
+use reqwest::{Client as ReqwestClient, ClientBuilder, header::{HeaderMap, HeaderName, HeaderValue}, Proxy, Response};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Semaphore;
 
+pub mod cookies;
+pub mod rate_limit;
+pub mod scheduler;
+pub mod site;
+
+use scheduler::ConnectionPermit;
+
 // 为了保持向后兼容性，提供一个类型别名
 pub type Client = RequestClient;
 
@@ -10,23 +19,59 @@ pub struct RequestClient {
     http: ReqwestClient,
     default_headers: HeaderMap,
     limiter: Arc<Semaphore>,
+    retry_count: Option<usize>,
+    /// 连接调度中的归属任务，同一任务的请求排在一个队列中
+    owner: Arc<str>,
 }
 
 const DEFAULT_CONCURRENCY: usize = 10;
 
+/// 构建客户端的选项，对应站点配置中的 `BaseParserConfig`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientOptions {
+    pub proxy_url: Option<String>,
+    /// 读取超时：两次收到数据之间的最长间隔，大文件下载不会因总耗时超时
+    pub timeout: Option<Duration>,
+    pub user_agent: Option<String>,
+    /// 附加在每个请求上的请求头，覆盖默认值
+    pub custom_headers: HashMap<String, String>,
+    /// 请求失败（网络错误、5xx、429）时的重试次数，未设置时解析请求不重试
+    pub retry_count: Option<usize>,
+}
+
 impl RequestClient {
     pub fn new(proxy_url: Option<String>) -> anyhow::Result<Self> {
+        Self::with_options(ClientOptions { proxy_url, ..ClientOptions::default() })
+    }
+
+    pub fn with_options(options: ClientOptions) -> anyhow::Result<Self> {
         let mut headers = HeaderMap::new();
         headers.insert("accept", "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7".parse()?);
         headers.insert("accept-language", "en,zh-CN;q=0.9,zh;q=0.8".parse()?);
         headers.insert("user-agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36".parse()?);
+        if let Some(ua) = options.user_agent.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            headers.insert("user-agent", HeaderValue::from_str(ua)?);
+        }
+        for (name, value) in &options.custom_headers {
+            let name = HeaderName::from_bytes(name.trim().as_bytes())
+                .map_err(|_| anyhow::anyhow!("无效的请求头名称: {}", name))?;
+            let value = HeaderValue::from_str(value.trim())
+                .map_err(|_| anyhow::anyhow!("无效的请求头 {} 的值", name))?;
+            headers.insert(name, value);
+        }
 
-        let mut builder = ClientBuilder::new().default_headers(headers.clone());
+        // 所有客户端共用持久化的 cookie 存储，响应中的 Set-Cookie 自动保存
+        let mut builder = ClientBuilder::new()
+            .default_headers(headers.clone())
+            .cookie_provider(cookies::cookie_jar().clone());
 
-        if let Some(p) = proxy_url.filter(|s| !s.is_empty()) {
+        if let Some(p) = options.proxy_url.filter(|s| !s.is_empty()) {
             let proxy = Proxy::all(&p)?;
             builder = builder.proxy(proxy);
         }
+        if let Some(timeout) = options.timeout.filter(|t| !t.is_zero()) {
+            builder = builder.connect_timeout(timeout).read_timeout(timeout);
+        }
 
         let http = builder.build()?;
         // 默认请求并发上限：10（可在特定站点覆盖）
@@ -35,15 +80,86 @@ impl RequestClient {
             http,
             default_headers: headers,
             limiter,
+            retry_count: options.retry_count,
+            owner: Arc::from(scheduler::SHARED_OWNER),
         })
     }
 
+    /// 站点配置的重试次数，下载器据此覆盖默认值
+    pub fn retry_count(&self) -> Option<usize> {
+        self.retry_count
+    }
+
+    /// 返回使用指定重试次数的克隆客户端
+    pub fn with_retry_count(mut self, retry_count: Option<usize>) -> Self {
+        self.retry_count = retry_count;
+        self
+    }
+
+    /// 返回归属于指定任务的克隆客户端，连接名额在任务之间轮流分配
+    pub fn for_task(&self, task_id: &str) -> Self {
+        Self { owner: Arc::from(task_id), ..self.clone() }
+    }
+
+    /// 按主机限速并占用连接名额后发送请求，根据响应调整该主机的速率
+    async fn send_throttled(
+        &self,
+        url: &str,
+        request: reqwest::RequestBuilder,
+    ) -> anyhow::Result<(reqwest::Result<Response>, ConnectionPermit)> {
+        let host = url::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_ascii_lowercase()));
+        let Some(host) = host else { return Ok((request.send().await, ConnectionPermit::unscheduled())); };
+        let limiter = rate_limit::host_limiter();
+        // 先等待限速再占用连接，避免被限流的主机占住名额
+        limiter.acquire(&host).await;
+        let permit = scheduler::connection_scheduler().acquire(&self.owner, &host).await?;
+        let result = request.send().await;
+        if let Ok(resp) = &result {
+            let retry_after = resp
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(rate_limit::parse_retry_after);
+            limiter.record(&host, resp.status(), retry_after);
+        }
+        Ok((result, permit))
+    }
+
+    /// 按重试次数发送请求，网络错误、5xx 与 429 时等待后重试（被限流时由主机限速器控制等待）
+    async fn send_with_retry(
+        &self,
+        url: &str,
+        build: impl Fn() -> reqwest::RequestBuilder,
+    ) -> anyhow::Result<(Response, ConnectionPermit)> {
+        let retries = self.retry_count.unwrap_or(0);
+        let mut attempt = 0;
+        loop {
+            let (result, permit) = self.send_throttled(url, build()).await?;
+            let retryable = match &result {
+                Ok(resp) => resp.status().is_server_error() || resp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS,
+                Err(e) => !e.is_builder(),
+            };
+            if !retryable || attempt >= retries {
+                return Ok((result?, permit));
+            }
+            drop(permit);
+            attempt += 1;
+            tracing::warn!(attempt, "request failed, retrying");
+            tokio::time::sleep(Duration::from_secs(attempt as u64)).await;
+        }
+    }
+
+    // 不占用客户端并发名额的 GET，仍经过主机限速与连接调度
     pub async fn get(&self, url: &str) -> anyhow::Result<Response> {
-        Ok(self.http.get(url).send().await?)
+        let (result, _permit) = self.send_throttled(url, self.http.get(url)).await?;
+        Ok(result?)
     }
 
     pub async fn head(&self, url: &str) -> anyhow::Result<Response> {
-        Ok(self.http.head(url).send().await?)
+        let (result, _permit) = self.send_throttled(url, self.http.head(url)).await?;
+        Ok(result?)
     }
 
     // 带并发限制的 GET
@@ -54,7 +170,7 @@ impl RequestClient {
             .acquire_owned()
             .await
             .map_err(|_| anyhow::anyhow!("semaphore closed"))?;
-        self.get(url).await
+        Ok(self.send_with_retry(url, || self.http.get(url)).await?.0)
     }
 
     // 带并发限制与额外请求头的 GET
@@ -63,6 +179,16 @@ impl RequestClient {
         url: &str,
         headers: &HeaderMap,
     ) -> anyhow::Result<Response> {
+        Ok(self.get_with_headers_holding_connection(url, headers).await?.0)
+    }
+
+    /// 同 `get_with_headers_rate_limited`，同时返回连接许可；
+    /// 读取较大的响应体（图片下载）时持有许可，读完再释放
+    pub async fn get_with_headers_holding_connection(
+        &self,
+        url: &str,
+        headers: &HeaderMap,
+    ) -> anyhow::Result<(Response, ConnectionPermit)> {
         let _permit = self
             .limiter
             .clone()
@@ -75,13 +201,9 @@ impl RequestClient {
         for (key, value) in headers.iter() {
             merged_headers.insert(key, value.clone());
         }
+        cookies::cookie_jar().merge_into(url, &mut merged_headers);
 
-        Ok(self
-            .http
-            .get(url)
-            .headers(merged_headers)
-            .send()
-            .await?)
+        self.send_with_retry(url, || self.http.get(url).headers(merged_headers.clone())).await
     }
 
     // 带并发限制的 POST 请求
@@ -103,14 +225,12 @@ impl RequestClient {
         for (key, value) in headers.iter() {
             merged_headers.insert(key, value.clone());
         }
+        cookies::cookie_jar().merge_into(url, &mut merged_headers);
 
-        Ok(self
-            .http
-            .post(url)
-            .headers(merged_headers)
-            .body(body)
-            .send()
-            .await?)
+        let (result, _permit) = self
+            .send_throttled(url, self.http.post(url).headers(merged_headers).body(body))
+            .await?;
+        Ok(result?)
     }
 
     // 返回一个设置了新并发上限的克隆客户端（不影响原实例）
@@ -119,6 +239,8 @@ impl RequestClient {
             http: self.http.clone(),
             default_headers: self.default_headers.clone(),
             limiter: Arc::new(Semaphore::new(permits)),
+            retry_count: self.retry_count,
+            owner: self.owner.clone(),
         }
     }
 }
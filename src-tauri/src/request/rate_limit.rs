@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+use reqwest::StatusCode;
+
+/// 被限流后每次减速的倍数上限
+const MAX_SLOWDOWN: f64 = 32.0;
+/// 每次成功请求后减速倍数的恢复比例
+const RECOVERY_FACTOR: f64 = 0.9;
+/// 未配置速率的主机被限流后，以此速率为基准减速（次/秒）
+const BACKOFF_BASE_RATE: f64 = 4.0;
+/// `Retry-After` 的最长等待时间，避免异常值让任务长时间挂起
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(300);
+
+/// 令牌桶参数：每秒请求数与突发数量
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub per_second: f64,
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// 由站点配置得到速率，未配置或无效时返回 None
+    pub fn from_config(per_second: Option<f64>, burst: Option<u32>) -> Option<Self> {
+        let per_second = per_second.filter(|r| r.is_finite() && *r > 0.0)?;
+        Some(Self { per_second, burst: burst.unwrap_or(1).max(1) })
+    }
+}
+
+/// 内置的站点默认速率，配置中可覆盖
+pub fn default_site_rate(site: &str) -> Option<RateLimit> {
+    match site {
+        "ehentai" => Some(RateLimit { per_second: 3.0, burst: 5 }),
+        "wnacg" => Some(RateLimit { per_second: 1.0, burst: 2 }),
+        "hitomi" => Some(RateLimit { per_second: 5.0, burst: 10 }),
+        _ => None,
+    }
+}
+
+/// 不在解析器域名内、但属于某个站点的资源域名（图片、列表文件 CDN），按所属站点限速
+const SITE_RESOURCE_DOMAINS: &[(&str, &str)] = &[("gold-usergeneratedcontent.net", "hitomi")];
+
+/// 主机所属的站点类型
+fn site_for_host(host: &str) -> Option<&'static str> {
+    crate::crawler::site_type_for_url(&format!("https://{}/", host)).or_else(|| {
+        SITE_RESOURCE_DOMAINS
+            .iter()
+            .find(|(domain, _)| host == *domain || host.ends_with(&format!(".{}", domain)))
+            .map(|(_, site)| *site)
+    })
+}
+
+struct HostState {
+    site: Option<&'static str>,
+    rate: Option<RateLimit>,
+    tokens: f64,
+    last_refill: Instant,
+    /// 当前减速倍数，1.0 表示正常速率
+    slowdown: f64,
+    blocked_until: Option<Instant>,
+}
+
+impl HostState {
+    fn new(site: Option<&'static str>, rate: Option<RateLimit>, now: Instant) -> Self {
+        Self {
+            site,
+            rate,
+            tokens: rate.map(|r| r.burst as f64).unwrap_or(0.0),
+            last_refill: now,
+            slowdown: 1.0,
+            blocked_until: None,
+        }
+    }
+
+    /// 当前生效的 (每秒请求数, 突发数量)，None 表示不限速
+    fn effective(&self) -> Option<(f64, f64)> {
+        match self.rate {
+            Some(rate) => Some((rate.per_second / self.slowdown, rate.burst as f64)),
+            None if self.slowdown > 1.0 => Some((BACKOFF_BASE_RATE / self.slowdown, 1.0)),
+            None => None,
+        }
+    }
+
+    /// 尝试取得一个令牌，失败时返回需要等待的时间
+    fn try_take(&mut self, now: Instant) -> Option<Duration> {
+        if let Some(until) = self.blocked_until {
+            if now < until {
+                return Some(until - now);
+            }
+            self.blocked_until = None;
+        }
+        let (rate, burst) = self.effective()?;
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(burst);
+        self.last_refill = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / rate))
+        }
+    }
+
+    /// 被限流：加倍减速，并暂停到 `Retry-After` 指定的时间
+    fn throttled(&mut self, retry_after: Option<Duration>, now: Instant) {
+        self.slowdown = (self.slowdown * 2.0).min(MAX_SLOWDOWN);
+        self.tokens = 0.0;
+        self.last_refill = now;
+        let pause = retry_after
+            .unwrap_or_else(|| Duration::from_secs_f64(self.slowdown))
+            .min(MAX_RETRY_AFTER);
+        let until = now + pause;
+        self.blocked_until = Some(self.blocked_until.map_or(until, |b| b.max(until)));
+    }
+
+    /// 请求成功：逐步恢复到正常速率
+    fn recovered(&mut self) {
+        if self.slowdown > 1.0 {
+            self.slowdown = (self.slowdown * RECOVERY_FACTOR).max(1.0);
+        }
+    }
+}
+
+/// 按主机限速的令牌桶，所有任务与客户端共享
+#[derive(Default)]
+pub struct HostRateLimiter {
+    hosts: Mutex<HashMap<String, HostState>>,
+    /// 站点配置的速率（None 表示明确不限速）
+    site_rates: RwLock<HashMap<&'static str, Option<RateLimit>>>,
+}
+
+static LIMITER: Lazy<HostRateLimiter> = Lazy::new(HostRateLimiter::default);
+
+pub fn host_limiter() -> &'static HostRateLimiter {
+    &LIMITER
+}
+
+impl HostRateLimiter {
+    /// 更新站点速率，已有主机的令牌桶立即生效
+    pub fn set_site_rate(&self, site: &'static str, rate: Option<RateLimit>) {
+        if self.site_rates.read().get(site) == Some(&rate) {
+            return;
+        }
+        self.site_rates.write().insert(site, rate);
+        for state in self.hosts.lock().values_mut().filter(|s| s.site == Some(site)) {
+            state.rate = rate;
+            state.tokens = state.tokens.min(rate.map(|r| r.burst as f64).unwrap_or(0.0));
+        }
+    }
+
+    fn site_rate(&self, site: &'static str) -> Option<RateLimit> {
+        self.site_rates
+            .read()
+            .get(site)
+            .copied()
+            .unwrap_or_else(|| default_site_rate(site))
+    }
+
+    /// 等待直到可以向该主机发送请求
+    pub async fn acquire(&self, host: &str) {
+        if !self.hosts.lock().contains_key(host) {
+            // 站点识别需要读取解析器注册表，不在持有锁时进行
+            let site = site_for_host(host);
+            let rate = site.and_then(|s| self.site_rate(s));
+            self.hosts
+                .lock()
+                .entry(host.to_string())
+                .or_insert_with(|| HostState::new(site, rate, Instant::now()));
+        }
+        loop {
+            let wait = match self.hosts.lock().get_mut(host) {
+                Some(state) => state.try_take(Instant::now()),
+                None => None,
+            };
+            match wait {
+                Some(wait) => tokio::time::sleep(wait).await,
+                None => return,
+            }
+        }
+    }
+
+    /// 根据响应调整该主机的速率：429/503 时减速，成功时逐步恢复
+    pub fn record(&self, host: &str, status: StatusCode, retry_after: Option<Duration>) {
+        let mut hosts = self.hosts.lock();
+        let Some(state) = hosts.get_mut(host) else { return; };
+        if status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE {
+            state.throttled(retry_after, Instant::now());
+            tracing::warn!(host = %host, status = %status, slowdown = state.slowdown, "host throttled, slowing down");
+        } else if status.is_success() {
+            state.recovered();
+        }
+    }
+}
+
+/// 解析 `Retry-After`：秒数或 HTTP 日期
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let secs = (date.with_timezone(&chrono::Utc) - chrono::Utc::now()).num_seconds();
+    Some(Duration::from_secs(secs.max(0) as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hitomi_pages_and_cdn_share_site_rate() {
+        assert_eq!(site_for_host("hitomi.la"), Some("hitomi"));
+        assert_eq!(site_for_host("w1.gold-usergeneratedcontent.net"), Some("hitomi"));
+        assert!(default_site_rate("hitomi").is_some());
+    }
+
+    #[test]
+    fn bucket_allows_burst_then_waits_for_refill() {
+        let now = Instant::now();
+        let mut state = HostState::new(None, Some(RateLimit { per_second: 2.0, burst: 2 }), now);
+
+        assert_eq!(state.try_take(now), None);
+        assert_eq!(state.try_take(now), None);
+        assert_eq!(state.try_take(now), Some(Duration::from_millis(500)));
+        assert_eq!(state.try_take(now + Duration::from_millis(500)), None);
+    }
+
+    #[test]
+    fn throttling_honors_retry_after_and_recovers_gradually() {
+        let now = Instant::now();
+        let mut state = HostState::new(None, Some(RateLimit { per_second: 4.0, burst: 1 }), now);
+
+        state.throttled(Some(Duration::from_secs(10)), now);
+        assert_eq!(state.try_take(now + Duration::from_secs(5)), Some(Duration::from_secs(5)));
+        assert_eq!(state.effective(), Some((2.0, 1.0)));
+
+        for _ in 0..3 {
+            state.recovered();
+        }
+        assert!(state.slowdown > 1.0 && state.slowdown < 2.0);
+        for _ in 0..10 {
+            state.recovered();
+        }
+        assert_eq!(state.effective(), Some((4.0, 1.0)));
+    }
+
+    #[test]
+    fn unlimited_host_is_slowed_only_after_throttling() {
+        let now = Instant::now();
+        let mut state = HostState::new(None, None, now);
+        assert_eq!(state.try_take(now), None);
+
+        state.throttled(None, now);
+
+        assert_eq!(state.try_take(now), Some(Duration::from_secs(2)));
+        assert_eq!(state.effective(), Some((BACKOFF_BASE_RATE / 2.0, 1.0)));
+    }
+
+    #[test]
+    fn parses_retry_after_seconds_and_dates() {
+        assert_eq!(parse_retry_after(" 120 "), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use tokio::sync::oneshot;
+
+use crate::config::ConnectionConfig;
+
+/// 未关联任务的请求（批量解析、订阅检查等）共用的调度键
+pub const SHARED_OWNER: &str = "";
+
+/// 所有任务共享的连接调度器：限制总连接数与单主机连接数，
+/// 有等待时按任务轮流分配，新增任务不会成倍增加对同一站点的压力
+#[derive(Clone)]
+pub struct ConnectionScheduler {
+    inner: Arc<Mutex<SchedulerState>>,
+}
+
+static SCHEDULER: Lazy<ConnectionScheduler> = Lazy::new(|| ConnectionScheduler::new(ConnectionConfig::default()));
+
+pub fn connection_scheduler() -> &'static ConnectionScheduler {
+    &SCHEDULER
+}
+
+struct Waiter {
+    host: String,
+    tx: oneshot::Sender<ConnectionPermit>,
+}
+
+struct SchedulerState {
+    config: ConnectionConfig,
+    active_total: usize,
+    active_hosts: HashMap<String, usize>,
+    /// 每个任务的等待队列（先到先得）
+    queues: HashMap<Arc<str>, VecDeque<Waiter>>,
+    /// 有等待请求的任务，按轮转顺序排列
+    order: VecDeque<Arc<str>>,
+}
+
+impl SchedulerState {
+    fn host_limit(&self, host: &str) -> usize {
+        self.config
+            .host_limits
+            .iter()
+            .filter(|(suffix, _)| host == suffix.as_str() || host.ends_with(&format!(".{}", suffix)))
+            .map(|(_, limit)| *limit)
+            .min()
+            .unwrap_or(self.config.max_connections_per_host)
+            .max(1)
+    }
+
+    fn has_capacity(&self, host: &str) -> bool {
+        self.active_total < self.config.max_connections.max(1)
+            && self.active_hosts.get(host).copied().unwrap_or(0) < self.host_limit(host)
+    }
+
+    fn take(&mut self, host: &str) {
+        self.active_total += 1;
+        *self.active_hosts.entry(host.to_string()).or_insert(0) += 1;
+    }
+
+    fn release(&mut self, host: &str) {
+        self.active_total = self.active_total.saturating_sub(1);
+        if let Some(count) = self.active_hosts.get_mut(host) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                self.active_hosts.remove(host);
+            }
+        }
+    }
+
+    /// 按任务轮转分配空闲连接，直到没有可分配的等待请求
+    fn dispatch(&mut self, inner: &Arc<Mutex<SchedulerState>>) {
+        let mut idle = 0;
+        while idle < self.order.len() && self.active_total < self.config.max_connections.max(1) {
+            let Some(owner) = self.order.pop_front() else { break; };
+            let mut queue = self.queues.remove(&owner).unwrap_or_default();
+            // 等待方已放弃（任务取消等）的请求直接移除
+            queue.retain(|w| !w.tx.is_closed());
+
+            match queue.iter().position(|w| self.has_capacity(&w.host)) {
+                Some(pos) => {
+                    let waiter = queue.remove(pos).expect("position is in range");
+                    self.take(&waiter.host);
+                    let permit = ConnectionPermit { host: waiter.host.clone(), inner: Some(inner.clone()) };
+                    if let Err(mut permit) = waiter.tx.send(permit) {
+                        // 已在持锁状态，不能经 Drop 再次加锁
+                        permit.inner = None;
+                        self.release(&waiter.host);
+                    }
+                    idle = 0;
+                }
+                None => idle += 1,
+            }
+
+            if !queue.is_empty() {
+                self.queues.insert(owner.clone(), queue);
+                self.order.push_back(owner);
+            }
+        }
+    }
+}
+
+/// 占用的连接，释放时把连接分配给下一个等待的任务
+pub struct ConnectionPermit {
+    host: String,
+    inner: Option<Arc<Mutex<SchedulerState>>>,
+}
+
+impl ConnectionPermit {
+    /// 不占用名额的许可（无法识别主机的请求）
+    pub fn unscheduled() -> Self {
+        Self { host: String::new(), inner: None }
+    }
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.take() {
+            let mut state = inner.lock();
+            state.release(&self.host);
+            state.dispatch(&inner);
+        }
+    }
+}
+
+impl ConnectionScheduler {
+    pub fn new(config: ConnectionConfig) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(SchedulerState {
+                config,
+                active_total: 0,
+                active_hosts: HashMap::new(),
+                queues: HashMap::new(),
+                order: VecDeque::new(),
+            })),
+        }
+    }
+
+    /// 更新连接上限，立即生效
+    pub fn set_config(&self, config: ConnectionConfig) {
+        let mut state = self.inner.lock();
+        state.config = config;
+        state.dispatch(&self.inner);
+    }
+
+    /// 等待一个到该主机的连接名额
+    pub async fn acquire(&self, owner: &str, host: &str) -> anyhow::Result<ConnectionPermit> {
+        let rx = {
+            let mut state = self.inner.lock();
+            if state.order.is_empty() && state.has_capacity(host) {
+                state.take(host);
+                return Ok(ConnectionPermit { host: host.to_string(), inner: Some(self.inner.clone()) });
+            }
+            let (tx, rx) = oneshot::channel();
+            let owner: Arc<str> = Arc::from(owner);
+            if state.queues.get(&owner).is_none_or(|q| q.is_empty()) {
+                state.order.push_back(owner.clone());
+            }
+            state.queues.entry(owner).or_default().push_back(Waiter { host: host.to_string(), tx });
+            state.dispatch(&self.inner);
+            rx
+        };
+        rx.await.map_err(|_| anyhow::anyhow!("connection scheduler closed"))
+    }
+
+    #[cfg(test)]
+    fn active(&self, host: &str) -> usize {
+        self.inner.lock().active_hosts.get(host).copied().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn scheduler(total: usize, per_host: usize) -> ConnectionScheduler {
+        ConnectionScheduler::new(ConnectionConfig {
+            max_connections: total,
+            max_connections_per_host: per_host,
+            host_limits: HashMap::new(),
+        })
+    }
+
+    #[tokio::test]
+    async fn enforces_per_host_cap_across_tasks() {
+        let scheduler = scheduler(10, 2);
+        let a = scheduler.acquire("task-a", "example.com").await.unwrap();
+        let _b = scheduler.acquire("task-b", "example.com").await.unwrap();
+
+        let blocked = tokio::time::timeout(Duration::from_millis(50), scheduler.acquire("task-c", "example.com")).await;
+        assert!(blocked.is_err());
+        // 其他主机不受影响
+        let _other = scheduler.acquire("task-c", "cdn.example.org").await.unwrap();
+
+        drop(a);
+        let _c = scheduler.acquire("task-c", "example.com").await.unwrap();
+        assert_eq!(scheduler.active("example.com"), 2);
+    }
+
+    #[tokio::test]
+    async fn grants_waiting_tasks_round_robin() {
+        let scheduler = scheduler(1, 1);
+        let first = scheduler.acquire("task-a", "example.com").await.unwrap();
+
+        let granted = Arc::new(Mutex::new(Vec::new()));
+        let mut handles = Vec::new();
+        for (owner, n) in [("task-a", 1), ("task-a", 2), ("task-a", 3), ("task-b", 1)] {
+            let scheduler = scheduler.clone();
+            let granted = granted.clone();
+            handles.push(tokio::spawn(async move {
+                let permit = scheduler.acquire(owner, "example.com").await.unwrap();
+                granted.lock().push(format!("{}#{}", owner, n));
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                drop(permit);
+            }));
+            // 保证入队顺序
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        drop(first);
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        assert_eq!(*granted.lock(), vec!["task-a#1", "task-b#1", "task-a#2", "task-a#3"]);
+    }
+
+    #[tokio::test]
+    async fn host_limits_override_default_cap() {
+        let scheduler = ConnectionScheduler::new(ConnectionConfig {
+            max_connections: 10,
+            max_connections_per_host: 4,
+            host_limits: HashMap::from([("e-hentai.org".to_string(), 1)]),
+        });
+        let _a = scheduler.acquire(SHARED_OWNER, "e-hentai.org").await.unwrap();
+
+        // 上限按主机分别计算
+        let other_host = tokio::time::timeout(Duration::from_millis(50), scheduler.acquire(SHARED_OWNER, "api.e-hentai.org")).await;
+        assert!(other_host.is_ok());
+        let blocked = tokio::time::timeout(Duration::from_millis(50), scheduler.acquire(SHARED_OWNER, "e-hentai.org")).await;
+        assert!(blocked.is_err());
+    }
+}
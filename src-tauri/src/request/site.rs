@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::RwLock;
+
+use super::{ClientOptions, RequestClient};
+use crate::config::parser_config::BaseParserConfig;
+
+/// 由站点配置得到客户端选项；`use_proxy` 明确为 false 时该站点直连
+pub fn options_for_site(base: &BaseParserConfig, global_proxy: &str) -> ClientOptions {
+    ClientOptions {
+        proxy_url: Some(global_proxy.to_string()).filter(|p| base.use_proxy.unwrap_or(true) && !p.is_empty()),
+        timeout: base.timeout.filter(|ms| *ms > 0).map(Duration::from_millis),
+        user_agent: base.user_agent.clone().filter(|ua| !ua.trim().is_empty()),
+        custom_headers: base.custom_headers.clone(),
+        retry_count: base.retry_count,
+    }
+}
+
+/// 按站点构建并缓存客户端，配置变化后下次获取时自动重建
+#[derive(Clone, Default)]
+pub struct SiteClients {
+    cache: Arc<RwLock<HashMap<String, (ClientOptions, RequestClient)>>>,
+}
+
+impl SiteClients {
+    pub fn get(&self, site: &str, options: ClientOptions) -> anyhow::Result<RequestClient> {
+        if let Some((cached_options, client)) = self.cache.read().get(site) {
+            if *cached_options == options {
+                return Ok(client.clone());
+            }
+        }
+        let client = RequestClient::with_options(options.clone())?;
+        self.cache.write().insert(site.to_string(), (options, client.clone()));
+        Ok(client)
+    }
+
+    pub fn clear(&self) {
+        self.cache.write().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_parser_config_to_client_options() {
+        let base = BaseParserConfig {
+            timeout: Some(15_000),
+            retry_count: Some(5),
+            user_agent: Some("  ".to_string()),
+            custom_headers: HashMap::from([("x-test".to_string(), "1".to_string())]),
+            use_proxy: Some(false),
+            ..BaseParserConfig::default()
+        };
+
+        let options = options_for_site(&base, "http://127.0.0.1:7890");
+
+        assert_eq!(options.proxy_url, None);
+        assert_eq!(options.timeout, Some(Duration::from_secs(15)));
+        assert_eq!(options.user_agent, None);
+        assert_eq!(options.retry_count, Some(5));
+        assert_eq!(
+            options_for_site(&BaseParserConfig { use_proxy: Some(true), ..base }, "http://127.0.0.1:7890").proxy_url.as_deref(),
+            Some("http://127.0.0.1:7890")
+        );
+    }
+
+    #[test]
+    fn saved_config_without_proxy_choice_uses_global_proxy() {
+        // 设置页保存的 pixiv 配置只有 cookies，旧版本还会带上从未生效的 `proxy_enabled: false`
+        let config: crate::config::parser_config::ParserConfig = serde_json::from_value(serde_json::json!({
+            "base": { "concurrency": 3, "custom_headers": {}, "proxy_enabled": false },
+            "auth": { "cookies": "PHPSESSID=1" },
+            "site_specific": null
+        }))
+        .unwrap();
+
+        let options = options_for_site(&config.base, "http://127.0.0.1:7890");
+
+        assert_eq!(options.proxy_url.as_deref(), Some("http://127.0.0.1:7890"));
+        assert!(serde_json::to_value(&config.base).unwrap().get("use_proxy").is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_custom_headers() {
+        let options = ClientOptions {
+            custom_headers: HashMap::from([("bad header".to_string(), "1".to_string())]),
+            ..ClientOptions::default()
+        };
+
+        assert!(SiteClients::default().get("test", options).is_err());
+    }
+}
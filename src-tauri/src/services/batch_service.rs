@@ -1,7 +1,7 @@
 use tauri::{AppHandle, Emitter};
 
 use crate::AppState;
-use crate::batch_crawler;
+use crate::batch_crawler::{self, filter::{self, BatchFilter}, BatchItem, ListingLimit};
 
 /// 批量服务错误类型
 #[derive(Debug)]
@@ -21,6 +21,16 @@ impl std::fmt::Display for BatchError {
 
 impl std::error::Error for BatchError {}
 
+/// 批量解析预览结果
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchPreview {
+    pub source_url: String,
+    /// 筛选前列表中的漫画数量
+    pub total: usize,
+    pub items: Vec<BatchItem>,
+}
+
 /// 批量服务
 pub struct BatchService;
 
@@ -29,41 +39,77 @@ impl BatchService {
         Self
     }
 
-    /// 启动批量爬虫任务
+    /// 启动批量爬虫任务：提取列表中的全部漫画并直接入队
     pub async fn start_batch_crawl(
         &self,
         url: String,
         app: AppHandle,
         state: &AppState,
     ) -> Result<Vec<String>, BatchError> {
+        let preview = self.preview_batch_crawl(url, BatchFilter::default(), &app, state).await?;
+        let urls = preview.items.into_iter().map(|item| item.url).collect();
+        self.enqueue_batch(preview.source_url, urls, app, state).await
+    }
+
+    /// 第一阶段：提取列表中的漫画并按条件筛选，不创建任务
+    pub async fn preview_batch_crawl(
+        &self,
+        url: String,
+        filter: BatchFilter,
+        app: &AppHandle,
+        state: &AppState,
+    ) -> Result<BatchPreview, BatchError> {
         // 获取必要配置
-        let client = state.request.read().clone();
+        let client = state
+            .client_for_url(&url)
+            .map_err(|e| BatchError::CrawlError(e.to_string()))?;
 
-        // 1. 提取所有漫画链接
-        let manga_links = batch_crawler::extract_manga_links_auto(
+        // 1. 提取列表范围内的漫画链接，筛选后的数量足够时不再翻页
+        let limit = ListingLimit::from_filter(&filter);
+        let items = batch_crawler::extract_manga_items_auto(
             &client,
             &url,
+            &limit,
             None,
             Some(state),
         ).await.map_err(|e| BatchError::CrawlError(e.to_string()))?;
 
-        if manga_links.is_empty() {
+        if items.is_empty() {
             return Err(BatchError::CrawlError("未找到任何漫画链接".to_string()));
         }
 
+        let total = items.len();
+        let items = filter::apply(items, &filter);
+
         // 发送批量解析完成事件
         let _ = app.emit("batch:extracted", serde_json::json!({
             "url": url,
-            "count": manga_links.len()
+            "count": total,
+            "selected": items.len()
         }));
 
-        // 2. 为每个漫画链接创建下载任务
+        Ok(BatchPreview { source_url: url, total, items })
+    }
+
+    /// 第二阶段：为选中的漫画链接创建下载任务
+    pub async fn enqueue_batch(
+        &self,
+        source_url: String,
+        urls: Vec<String>,
+        app: AppHandle,
+        state: &AppState,
+    ) -> Result<Vec<String>, BatchError> {
+        if urls.is_empty() {
+            return Err(BatchError::TaskError("未选择任何漫画".to_string()));
+        }
+
         let mut task_ids = Vec::new();
 
-        for manga_url in manga_links {
+        for manga_url in urls {
             // 创建任务
             let task_id = state.task_service.start_crawl_task(
                 manga_url.clone(),
+                Vec::new(),
                 app.clone(),
                 state,
             ).await.map_err(|e| BatchError::TaskError(e.to_string()))?;
@@ -76,7 +122,7 @@ impl BatchService {
 
         // 发送批量任务创建完成事件
         let _ = app.emit("batch:started", serde_json::json!({
-            "sourceUrl": url,
+            "sourceUrl": source_url,
             "taskIds": task_ids,
             "totalTasks": task_ids.len()
         }));
@@ -3,6 +3,7 @@ use tokio_util::sync::CancellationToken;
 
 use crate::crawler;
 use crate::download;
+use crate::export::comic_info::ComicInfo;
 use crate::progress;
 use crate::request::Client;
 use crate::task::manager::TaskManager;
@@ -32,10 +33,11 @@ pub struct CrawlService;
 
 impl CrawlService {
 
-    /// 解析并验证URL
+    /// 解析并验证URL，`chapters` 为选中的章节（为空表示全部）
     pub async fn parse_and_validate(
         client: &Client,
         url: &str,
+        chapters: &[usize],
         task_id: &str,
         task_manager: &Arc<parking_lot::RwLock<TaskManager>>,
         cancel_token: &CancellationToken,
@@ -51,7 +53,7 @@ impl CrawlService {
         let parsed = tokio::select! {
             biased;
             _ = cancel_token.cancelled() => return Err(CrawlError::Cancelled),
-            res = crawler::parse_gallery_auto(client, url, Some(reporter), app_state) => {
+            res = crawler::parse_gallery_auto(client, url, chapters, Some(reporter), app_state) => {
                 res.map_err(|e| CrawlError::ParseFailed(e.to_string()))?
             }
         };
@@ -61,23 +63,68 @@ impl CrawlService {
             return Err(CrawlError::ValidationFailed("未解析到图片".to_string()));
         }
 
+        let mut parsed = parsed;
+        if parsed.metadata.source_url.is_none() {
+            parsed.metadata.source_url = Some(url.to_string());
+        }
+
         Ok(parsed)
     }
 
-    /// 构建下载计划
+    /// 构建下载计划，图片保存到 `save_path` 目录；多章节画廊的页面以章节序号为前缀，
+    /// 例如 `002_0001.jpg`，补下其他章节时不会覆盖已有页面
     pub fn build_download_plan(
         parsed: &crawler::ParsedGallery,
-        output_dir: &str,
+        save_path: &str,
     ) -> (Vec<String>, Vec<std::path::PathBuf>) {
-        let safe_name = sanitize_filename::sanitize(
-            parsed
-                .title
-                .clone()
-                .unwrap_or_else(|| "gallery".to_string()),
-        );
+        let base_path = std::path::Path::new(save_path);
+        if parsed.chapters.is_empty() {
+            return download::build_download_plan(&parsed.image_urls, base_path);
+        }
+        let mut urls = Vec::with_capacity(parsed.image_urls.len());
+        let mut paths = Vec::with_capacity(parsed.image_urls.len());
+        for chapter in &parsed.chapters {
+            let end = (chapter.start + chapter.count).min(parsed.image_urls.len());
+            let prefix = crawler::chapter_page_prefix(chapter.index);
+            for (page, url) in parsed.image_urls[chapter.start..end].iter().enumerate() {
+                let ext = download::infer_ext_from_url(url).unwrap_or("jpg");
+                urls.push(url.clone());
+                paths.push(base_path.join(format!("{}{:04}.{}", prefix, page + 1, ext)));
+            }
+        }
+        (urls, paths)
+    }
 
-        let base_path = std::path::PathBuf::from(output_dir).join(&safe_name);
-        download::build_download_plan(&parsed.image_urls, &base_path)
+    /// 列出章节，并标记输出目录中已下载的章节
+    pub async fn list_chapters(
+        client: &Client,
+        url: &str,
+        output_dir: &str,
+    ) -> Result<crawler::ChapterList, CrawlError> {
+        let mut list = crawler::list_chapters_auto(client, url)
+            .await
+            .map_err(|e| CrawlError::ParseFailed(e.to_string()))?;
+        let safe_name = sanitize_filename::sanitize(list.title.clone().unwrap_or_else(|| "gallery".to_string()));
+        let base_path = std::path::PathBuf::from(output_dir).join(safe_name);
+        let pages: Vec<String> = crate::library::Manager::default()
+            .get_manga_images(&base_path.to_string_lossy())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|p| std::path::Path::new(p).file_name().map(|n| n.to_string_lossy().to_string()))
+            .collect();
+        for chapter in &mut list.chapters {
+            let prefix = crawler::chapter_page_prefix(chapter.index);
+            chapter.downloaded = pages.iter().any(|name| name.starts_with(&prefix));
+        }
+        Ok(list)
+    }
+
+    /// 在画廊目录写入 ComicInfo.xml，失败只记录日志不影响下载
+    pub fn write_comic_info(parsed: &crawler::ParsedGallery, name: &str, save_path: &str) {
+        let info = ComicInfo::new(name, &parsed.metadata, parsed.image_urls.len());
+        if let Err(e) = info.write_to_dir(std::path::Path::new(save_path)) {
+            tracing::warn!(error = %e, save_path = %save_path, "failed to write ComicInfo.xml");
+        }
     }
 
     /// 准备任务信息
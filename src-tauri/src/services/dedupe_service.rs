@@ -0,0 +1,223 @@
+use std::path::Path;
+
+use tauri::AppHandle;
+
+use crate::AppState;
+use crate::config::service::ConfigService;
+use crate::crawler;
+use crate::export;
+use crate::history;
+use crate::task::TaskStatus;
+
+/// 重复来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DuplicateSource {
+    /// 任务列表中已有同一画廊的任务
+    Task,
+    /// 下载历史中已有同一画廊的记录
+    History,
+    /// 书库中已有来源相同的漫画
+    Library,
+    /// 目标目录已存在且包含图片
+    Folder,
+}
+
+/// 已存在的同一画廊
+#[derive(Debug, Clone)]
+pub struct Duplicate {
+    pub source: DuplicateSource,
+    pub path: String,
+}
+
+impl std::fmt::Display for Duplicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source = match self.source {
+            DuplicateSource::Task => "任务列表",
+            DuplicateSource::History => "下载历史",
+            DuplicateSource::Library => "书库",
+            DuplicateSource::Folder => "下载目录",
+        };
+        write!(f, "画廊已存在于{}: {}", source, self.path)
+    }
+}
+
+/// 下载前的重复检测
+pub struct DedupeService;
+
+impl DedupeService {
+    /// 按画廊 ID 查找已有下载，`task_id` 和 `own_save_path` 为任务自身，不算重复；
+    /// 只下载了部分章节的任务和记录不算完整下载
+    pub fn find_by_url(
+        url: &str,
+        task_id: &str,
+        own_save_path: &str,
+        app: &AppHandle,
+        state: &AppState,
+    ) -> Option<Duplicate> {
+        let key = crawler::gallery_key(url)?;
+        let same_gallery = |other: &str| crawler::gallery_key(other).as_deref() == Some(key.as_str());
+
+        let tasks = state.task_manager.read().all();
+        if let Some(task) = tasks.iter().find(|t| {
+            t.id != task_id
+                && t.chapters.is_empty()
+                && matches!(
+                    t.status,
+                    TaskStatus::Queued | TaskStatus::Parsing | TaskStatus::Running | TaskStatus::Completed | TaskStatus::PartialFailed
+                )
+                && same_gallery(&t.url)
+        }) {
+            let path = if task.save_path.is_empty() { task.url.clone() } else { task.save_path.clone() };
+            return Some(Duplicate { source: DuplicateSource::Task, path });
+        }
+
+        let mut history_manager = history::Manager::default();
+        if history_manager.set_dir_from_app(app).is_ok() {
+            let record = history_manager.get_history().into_iter().find(|r| {
+                r.id != task_id
+                    && r.chapters.is_empty()
+                    && matches!(r.status.as_str(), "completed" | "partial_failed")
+                    && r.save_path != own_save_path
+                    && still_on_disk(&r.save_path)
+                    && same_gallery(&r.url)
+            });
+            if let Some(record) = record {
+                return Some(Duplicate { source: DuplicateSource::History, path: record.save_path });
+            }
+        }
+
+        let roots = state.config.read().get_libraries();
+        let entry = state.library_index.read().entries(&roots).into_iter().find(|e| {
+            e.path != own_save_path && e.source_url.as_deref().is_some_and(same_gallery)
+        });
+        entry.map(|e| Duplicate { source: DuplicateSource::Library, path: e.path })
+    }
+
+    /// 目标目录已存在且包含图片时视为重复；`chapters` 不为空时只有这些章节都已有页面才算重复
+    pub fn find_existing_folder(save_path: &str, chapters: &[usize]) -> Option<Duplicate> {
+        let images = crate::library::Manager::default()
+            .get_manga_images(save_path)
+            .unwrap_or_default();
+        let has_images = if chapters.is_empty() {
+            !images.is_empty()
+        } else {
+            chapters.iter().all(|&index| {
+                let prefix = crawler::chapter_page_prefix(index);
+                images.iter().any(|p| {
+                    Path::new(p).file_name().is_some_and(|n| n.to_string_lossy().starts_with(&prefix))
+                })
+            })
+        };
+        if has_images || (chapters.is_empty() && export::cbz_path_for(Path::new(save_path)).is_file()) {
+            return Some(Duplicate { source: DuplicateSource::Folder, path: save_path.to_string() });
+        }
+        None
+    }
+
+    /// 重新下载前删除目录中按序号命名的页面文件（`0001.jpg`、`002_0001.jpg`、`0001.jpg.part` 等），
+    /// 其他文件保留；`chapters` 不为空时只删除这些章节的页面，返回删除数量
+    pub fn clear_pages(save_path: &str, chapters: &[usize]) -> usize {
+        let Ok(entries) = std::fs::read_dir(save_path) else { return 0; };
+        let prefixes: Vec<String> = chapters.iter().map(|&i| crawler::chapter_page_prefix(i)).collect();
+        entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && is_page_file(p))
+            .filter(|p| {
+                prefixes.is_empty()
+                    || p.file_name().is_some_and(|n| prefixes.iter().any(|prefix| n.to_string_lossy().starts_with(prefix)))
+            })
+            .filter(|p| std::fs::remove_file(p).is_ok())
+            .count()
+    }
+
+    /// 生成不冲突的目录：`标题 (2)`、`标题 (3)` ...
+    pub fn suffixed_path(save_path: &str) -> String {
+        (2..)
+            .map(|n| format!("{} ({})", save_path, n))
+            .find(|candidate| !still_on_disk(candidate))
+            .unwrap_or_else(|| save_path.to_string())
+    }
+}
+
+fn is_page_file(path: &Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+    let name = name.strip_suffix(".part").unwrap_or(&name);
+    let Some((stem, ext)) = name.rsplit_once('.') else { return false; };
+    // 单章节为 `0001`，多章节为 `<章节>_<页码>`
+    !stem.is_empty()
+        && stem.split('_').count() <= 2
+        && stem.split('_').all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+        && matches!(ext, "jpg" | "jpeg" | "png" | "gif" | "webp" | "bmp" | "avif" | "zip")
+}
+
+/// 目录或导出后的 CBZ 仍在磁盘上
+fn still_on_disk(save_path: &str) -> bool {
+    if save_path.is_empty() {
+        return false;
+    }
+    let path = Path::new(save_path);
+    path.exists() || export::cbz_path_for(path).is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suffixed_path_skips_existing_folders_and_archives() {
+        let root = std::env::temp_dir().join(format!(
+            "hmanga-dedupe-test-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        let base = root.join("gallery");
+        std::fs::create_dir_all(&base).unwrap();
+        std::fs::create_dir_all(root.join("gallery (2)")).unwrap();
+        std::fs::write(root.join("gallery (3).cbz"), b"").unwrap();
+
+        let next = DedupeService::suffixed_path(&base.to_string_lossy());
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(next, root.join("gallery (4)").to_string_lossy());
+    }
+
+    #[test]
+    fn clear_pages_removes_only_numbered_page_files() {
+        let dir = std::env::temp_dir().join(format!(
+            "hmanga-dedupe-clear-test-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["0001.jpg", "0002.webp", "0003.png.part", "ComicInfo.xml", "cover-note.jpg", "002_0001.jpg", "003_0001.jpg"] {
+            std::fs::write(dir.join(name), b"x").unwrap();
+        }
+
+        let chapter_removed = DedupeService::clear_pages(&dir.to_string_lossy(), &[3]);
+        let removed = DedupeService::clear_pages(&dir.to_string_lossy(), &[]);
+        let mut left: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        let _ = std::fs::remove_dir_all(&dir);
+        left.sort();
+
+        assert_eq!(chapter_removed, 1);
+        assert_eq!(removed, 4);
+        assert_eq!(left, vec!["ComicInfo.xml", "cover-note.jpg"]);
+    }
+
+    #[test]
+    fn gallery_key_matches_equivalent_urls() {
+        assert_eq!(
+            crawler::gallery_key("https://e-hentai.org/g/123456/abcdef0123/?p=2").as_deref(),
+            Some("ehentai:123456")
+        );
+        assert_eq!(
+            crawler::gallery_key("https://exhentai.org/g/123456/abcdef0123/"),
+            crawler::gallery_key("https://e-hentai.org/g/123456/abcdef0123/")
+        );
+        assert_eq!(crawler::gallery_key("https://nhentai.net/g/537651/").as_deref(), Some("nhentai:537651"));
+        assert_eq!(crawler::gallery_key("https://example.test/g/1/"), None);
+    }
+}
@@ -0,0 +1,85 @@
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Emitter, Listener, Manager};
+
+use crate::AppState;
+use crate::config::service::ConfigService;
+use crate::export::{self, CbzOptions};
+
+/// 导出服务错误类型
+#[derive(Debug)]
+pub enum ExportError {
+    NotFound(String),
+    ExportFailed(String),
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportError::NotFound(msg) => write!(f, "导出目标不存在: {}", msg),
+            ExportError::ExportFailed(msg) => write!(f, "导出失败: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+/// 导出服务
+pub struct ExportService;
+
+impl ExportService {
+    /// 将画廊目录打包为 CBZ（在阻塞线程池中执行）
+    pub async fn export_cbz(path: String, delete_source: bool) -> Result<PathBuf, ExportError> {
+        let dir = PathBuf::from(&path);
+        if !dir.is_dir() {
+            return Err(ExportError::NotFound(path));
+        }
+        let options = CbzOptions { delete_source };
+        tokio::task::spawn_blocking(move || export::export_cbz(&dir, &options))
+            .await
+            .map_err(|e| ExportError::ExportFailed(e.to_string()))?
+            .map_err(|e| ExportError::ExportFailed(e.to_string()))
+    }
+
+    /// 监听 `download:completed`，按配置自动导出 CBZ
+    pub fn register_auto_export(app: &AppHandle) {
+        let handle = app.clone();
+        app.listen("download:completed", move |event| {
+            let task_id = serde_json::from_str::<serde_json::Value>(event.payload())
+                .ok()
+                .and_then(|v| v.get("taskId").and_then(|id| id.as_str()).map(|s| s.to_string()));
+            let Some(task_id) = task_id else { return; };
+
+            let state = handle.state::<AppState>();
+            let export_config = state.config.read().get_export_config();
+            if !export_config.auto_cbz {
+                return;
+            }
+            let Some(task) = state.task_manager.read().by_id(&task_id) else { return; };
+            if task.save_path.is_empty() {
+                return;
+            }
+
+            let app = handle.clone();
+            tauri::async_runtime::spawn(async move {
+                match Self::export_cbz(task.save_path.clone(), export_config.delete_after_export).await {
+                    Ok(cbz) => {
+                        let _ = app.emit("export:completed", serde_json::json!({
+                            "taskId": task.id,
+                            "taskName": task.name,
+                            "path": cbz.to_string_lossy(),
+                        }));
+                    }
+                    Err(e) => {
+                        tracing::warn!(task_id = %task.id, error = %e, "auto cbz export failed");
+                        let _ = app.emit("export:failed", serde_json::json!({
+                            "taskId": task.id,
+                            "taskName": task.name,
+                            "message": e.to_string(),
+                        }));
+                    }
+                }
+            });
+        });
+    }
+}
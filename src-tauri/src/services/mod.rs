@@ -2,8 +2,14 @@ pub mod crawl_service;
 pub mod history_service;
 pub mod task_service;
 pub mod batch_service;
+pub mod export_service;
+pub mod dedupe_service;
+pub mod subscription_service;
 
 pub use crawl_service::CrawlService;
 pub use history_service::HistoryService;
 pub use task_service::TaskService;
 pub use batch_service::BatchService;
+pub use export_service::ExportService;
+pub use dedupe_service::DedupeService;
+pub use subscription_service::SubscriptionService;
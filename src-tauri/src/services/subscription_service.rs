@@ -0,0 +1,111 @@
+use tauri::{AppHandle, Emitter};
+
+use crate::AppState;
+use crate::batch_crawler::{self, BatchItem};
+use crate::services::DedupeService;
+
+/// 一次订阅检查的结果
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckResult {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    /// 已创建任务的新画廊标题（列表页未提供时为 URL）
+    pub new_titles: Vec<String>,
+    pub task_ids: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// 订阅服务：定期重新解析订阅的列表，只为新出现的画廊创建下载任务
+pub struct SubscriptionService;
+
+impl SubscriptionService {
+    /// 检查所有到期的订阅（由定时器调用，逐个执行避免同时请求多个列表）
+    pub async fn check_due(app: &AppHandle, state: &AppState) {
+        let due = state.subscriptions.read().due(chrono::Utc::now());
+        for subscription in due {
+            if let Err(e) = Self::check(&subscription.id, false, app, state).await {
+                tracing::warn!(subscription = %subscription.url, error = %e, "subscription check failed");
+            }
+        }
+    }
+
+    /// 检查单个订阅，`include_existing` 为 true 时首次检查也下载列表中已有的画廊
+    pub async fn check(
+        id: &str,
+        include_existing: bool,
+        app: &AppHandle,
+        state: &AppState,
+    ) -> anyhow::Result<CheckResult> {
+        let subscription = state
+            .subscriptions
+            .read()
+            .get(id)
+            .ok_or_else(|| anyhow::anyhow!("订阅不存在: {}", id))?;
+        // 同一订阅同时只进行一次检查，否则两次检查会为同一批新画廊各建一次任务
+        if !state.subscriptions.write().begin_check(id) {
+            anyhow::bail!("订阅正在检查中: {}", subscription.name);
+        }
+        let _checking = CheckingGuard { state, id };
+        let mut result = CheckResult {
+            id: subscription.id.clone(),
+            name: subscription.name.clone(),
+            url: subscription.url.clone(),
+            ..CheckResult::default()
+        };
+
+        let crawled = match state.client_for_url(&subscription.url) {
+            Ok(client) => batch_crawler::extract_manga_items_auto(&client, &subscription.url, &subscription.listing_limit(), None, Some(state)).await,
+            Err(e) => Err(e),
+        };
+        let items = match crawled {
+            Ok(items) => items,
+            Err(e) => {
+                state.subscriptions.write().record_error(id, e.to_string())?;
+                result.error = Some(e.to_string());
+                let _ = app.emit("subscription:checked", &result);
+                return Err(e);
+            }
+        };
+
+        let new_items = state.subscriptions.write().record_items(id, items, include_existing)?;
+
+        // 已在任务列表、下载历史或书库中的画廊不再入队，直接标记为已见
+        let (downloaded, new_items): (Vec<BatchItem>, Vec<BatchItem>) = new_items
+            .into_iter()
+            .partition(|item| DedupeService::find_by_url(&item.url, "", "", app, state).is_some());
+        let downloaded: Vec<String> = downloaded.into_iter().map(|item| item.url).collect();
+        state.subscriptions.write().mark_seen(id, &downloaded)?;
+
+        // 逐个创建任务，只有任务创建成功的画廊才标记为已见，失败的下次检查重试
+        for item in new_items {
+            match state.task_service.start_crawl_task(item.url.clone(), Vec::new(), app.clone(), state).await {
+                Ok(task_id) => {
+                    state.subscriptions.write().mark_seen(id, std::slice::from_ref(&item.url))?;
+                    result.new_titles.push(item.title.unwrap_or(item.url));
+                    result.task_ids.push(task_id);
+                }
+                Err(e) => result.error = Some(e.to_string()),
+            }
+            // 短暂延迟，避免同时启动过多任务
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        }
+
+        tracing::info!(subscription = %subscription.url, new = result.new_titles.len(), "subscription checked");
+        let _ = app.emit("subscription:checked", &result);
+        Ok(result)
+    }
+}
+
+/// 检查结束（包括出错返回）时清除订阅的检查中标记
+struct CheckingGuard<'a> {
+    state: &'a AppState,
+    id: &'a str,
+}
+
+impl Drop for CheckingGuard<'_> {
+    fn drop(&mut self) {
+        self.state.subscriptions.write().end_check(self.id);
+    }
+}
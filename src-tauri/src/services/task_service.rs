@@ -7,7 +7,9 @@ use uuid::Uuid;
 use crate::AppState;
 use crate::config::service::ConfigService;
 use crate::history;
-use crate::services::{CrawlService};
+use crate::config::DuplicatePolicy;
+use crate::services::{CrawlService, DedupeService};
+use crate::services::dedupe_service::Duplicate;
 
 /// 任务服务错误类型
 #[derive(Debug)]
@@ -35,10 +37,11 @@ impl TaskService {
         Self
     }
 
-    /// 启动爬虫任务
+    /// 启动爬虫任务，`chapters` 为多章节画廊选择下载的章节（为空表示全部）
     pub async fn start_crawl_task(
         &self,
         url: String,
+        chapters: Vec<usize>,
         app: AppHandle,
         state: &AppState,
     ) -> Result<String, TaskError> {
@@ -48,23 +51,12 @@ impl TaskService {
         // 检查并发限制
         if state.task_manager.read().running_task_count() >= state.config.read().get_max_concurrent_tasks() {
             // 任务加入队列 - 直接创建为Queued状态
-            {
-                let task_manager = state.task_manager.read();
-                let mut w = task_manager.tasks.write();
-                let mut t = w.remove(&task_id).unwrap_or_default();
-                t.id = task_id.clone();
-                t.url = url.clone();
-                t.status = crate::task::TaskStatus::Queued;
-                t.progress = crate::task::Progress { current: 0, total: 0 };
-                t.start_time = chrono::Utc::now().to_rfc3339();
-                t.updated_at = t.start_time.clone();
-                w.insert(task_id.clone(), t);
-            }
+            state.task_manager.read().create_queued(&task_id, &url, &chapters);
             return Ok(task_id);
         }
 
         // 直接执行任务，先创建为Parsing状态
-        state.task_manager.read().create_or_start(&task_id, &url, 0);
+        state.task_manager.read().create_or_start(&task_id, &url, &chapters, 0);
         Self::execute_crawl_task_internal(&task_id, &url, &app, state).await?;
 
         Ok(task_id)
@@ -78,8 +70,22 @@ impl TaskService {
         state: &AppState,
     ) -> Result<(), TaskError> {
         // 获取必要配置
-        let client = state.request.read().clone();
         let output_dir = state.config.read().get_output_dir();
+        let policy = state.config.read().get_duplicate_policy();
+        let (own_save_path, chapters) = state
+            .task_manager
+            .read()
+            .by_id(task_id)
+            .map(|t| (t.save_path, t.chapters))
+            .unwrap_or_default();
+
+        // 解析前按画廊 ID 去重，避免重复请求站点；只下载部分章节时按章节在目录中检查
+        if policy == DuplicatePolicy::Skip && chapters.is_empty() {
+            if let Some(dup) = DedupeService::find_by_url(url, task_id, &own_save_path, app, state) {
+                Self::skip_duplicate(task_id, &dup, app, state);
+                return Ok(());
+            }
+        }
 
         // 创建取消令牌
         let cancel_token = CancellationToken::new();
@@ -88,15 +94,25 @@ impl TaskService {
             .write()
             .insert(task_id.to_string(), cancel_token.clone());
 
-        // 解析URL
-        let parsed = match CrawlService::parse_and_validate(
-            &client,
-            url,
-            task_id,
-            &state.task_manager,
-            &cancel_token,
-            Some(state),
-        ).await {
+        // 按站点配置构建客户端并解析URL
+        let parsed = match state.client_for_url(url) {
+            Ok(client) => {
+                let client = client.for_task(task_id);
+                CrawlService::parse_and_validate(
+                    &client,
+                    url,
+                    &chapters,
+                    task_id,
+                    &state.task_manager,
+                    &cancel_token,
+                    Some(state),
+                )
+                .await
+                .map(|parsed| (client, parsed))
+            }
+            Err(e) => Err(crate::services::crawl_service::CrawlError::ParseFailed(e.to_string())),
+        };
+        let (client, parsed) = match parsed {
             Ok(p) => p,
             Err(e) => {
                 // 处理解析错误 - 简化版本直接设置失败状态
@@ -106,9 +122,39 @@ impl TaskService {
             }
         };
 
+        let (mut name, mut save_path) = CrawlService::prepare_task_info(&parsed, &output_dir);
+
+        // 目标目录已有其他画廊的图片时按策略处理；任务自身的目录（重试/恢复）不算重复
+        // 多章节画廊只检查本次选中的章节，补下其他章节时不算重复
+        let selected: Vec<usize> = parsed.chapters.iter().map(|c| c.index).collect();
+        if save_path != own_save_path {
+            if let Some(dup) = DedupeService::find_existing_folder(&save_path, &selected) {
+                match policy {
+                    DuplicatePolicy::Skip => {
+                        state.cancels.write().remove(task_id);
+                        Self::skip_duplicate(task_id, &dup, app, state);
+                        return Ok(());
+                    }
+                    DuplicatePolicy::Suffix => {
+                        save_path = DedupeService::suffixed_path(&save_path);
+                        name = std::path::Path::new(&save_path)
+                            .file_name()
+                            .map(|n| n.to_string_lossy().to_string())
+                            .unwrap_or(name);
+                    }
+                    DuplicatePolicy::Redownload => {
+                        // 原有页面删除后重新获取，避免新旧页面混在一起
+                        DedupeService::clear_pages(&save_path, &selected);
+                    }
+                }
+            }
+        }
+
         // 构建下载计划
-        let (urls, paths) = CrawlService::build_download_plan(&parsed, &output_dir);
-        let (name, save_path) = CrawlService::prepare_task_info(&parsed, &output_dir);
+        let (urls, paths) = CrawlService::build_download_plan(&parsed, &save_path);
+
+        // 写入元数据文件，供媒体服务器索引
+        CrawlService::write_comic_info(&parsed, parsed.title.as_deref().unwrap_or(&name), &save_path);
 
         // 更新任务信息并切换到下载状态
         state.task_manager.read().set_name_and_path(task_id, &name, &save_path);
@@ -125,6 +171,7 @@ impl TaskService {
             token_opt: Some(cancel_token.clone()),
             default_headers: parsed.download_headers,
             concurrency_override: parsed.recommended_concurrency,
+            image_transform: parsed.image_transform,
         };
         let token = state.task_manager.read().start_batch_with_concurrency(batch_params);
 
@@ -134,6 +181,17 @@ impl TaskService {
         Ok(())
     }
 
+    /// 将任务标记为重复并通知前端
+    fn skip_duplicate(task_id: &str, dup: &Duplicate, app: &AppHandle, state: &AppState) {
+        tracing::info!(task_id = %task_id, path = %dup.path, "skip duplicate gallery");
+        state.task_manager.read().set_skipped(task_id, &dup.to_string(), &dup.path);
+        let _ = app.emit("download:skipped", serde_json::json!({
+            "taskId": task_id,
+            "path": dup.path,
+            "message": dup.to_string(),
+        }));
+    }
+
     /// 处理排队中的任务
     pub async fn process_queued_tasks(
         &self,
@@ -299,11 +357,14 @@ impl TaskService {
             .map(|file| PathBuf::from(&file.path))
             .collect::<Vec<_>>();
         let indices = failed_files.iter().map(|file| file.index).collect::<Vec<_>>();
-        let client = state.request.read().clone();
+        let client = state
+            .client_for_url(&task.url)
+            .map_err(|e| TaskError::CrawlError(e.to_string()))?;
         let header_probe_token = CancellationToken::new();
         let parsed_for_retry = CrawlService::parse_and_validate(
             &client,
             &task.url,
+            &task.chapters,
             task_id,
             &state.task_manager,
             &header_probe_token,
@@ -317,6 +378,9 @@ impl TaskService {
         let concurrency_override = parsed_for_retry
             .as_ref()
             .and_then(|parsed| parsed.recommended_concurrency);
+        let image_transform = parsed_for_retry
+            .as_ref()
+            .and_then(|parsed| parsed.image_transform.clone());
 
         state
             .task_manager
@@ -340,6 +404,7 @@ impl TaskService {
             token_opt: Some(cancel_token.clone()),
             default_headers,
             concurrency_override,
+            image_transform,
         };
         let token = state.task_manager.read().start_batch_with_concurrency(batch_params);
         state.cancels.write().insert(task_id.to_string(), token);
@@ -374,6 +439,7 @@ impl TaskService {
             "partial_failed" => TaskStatus::PartialFailed,
             "failed" => TaskStatus::Failed,
             "cancelled" => TaskStatus::Cancelled,
+            "skipped" => TaskStatus::Skipped,
             _ => TaskStatus::Pending,
         };
 
@@ -396,6 +462,8 @@ impl TaskService {
             updated_at: task_dto.updated_at.clone(),
             last_retry_time: String::new(), // 从历史恢复时重置
             retryable: task_dto.retryable,
+            chapters: task_dto.chapters.clone(),
+            resumed: false,
         };
 
         // 修复死锁：不要持有 task_manager 写锁的同时获取 tasks 写锁
@@ -0,0 +1,347 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use tauri::Manager as TauriManager;
+
+use crate::batch_crawler::{BatchItem, ListingLimit};
+use crate::crawler;
+
+const SUBSCRIPTIONS_FILE: &str = "subscriptions.json";
+
+/// 最短检查间隔（分钟），避免频繁请求列表页
+pub const MIN_INTERVAL_MINUTES: u64 = 10;
+
+/// 每次检查最多读取的列表页数，列表按新到旧排列，新画廊只会出现在前几页
+pub const MAX_CHECK_PAGES: usize = 2;
+
+/// 未设置时每次检查最多读取的画廊数
+pub const DEFAULT_MAX_ITEMS: usize = 50;
+
+/// 订阅的列表页（作者、标签、搜索结果等）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Subscription {
+    pub id: String,
+    pub url: String,
+    pub name: String,
+    pub interval_minutes: u64,
+    pub enabled: bool,
+    pub created_at: String,
+    #[serde(default)]
+    pub last_checked_at: Option<String>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// 最近一次检查发现的新画廊数量
+    #[serde(default)]
+    pub last_new_count: usize,
+    /// 每次检查最多读取的画廊数，未设置时为 [`DEFAULT_MAX_ITEMS`]
+    #[serde(default)]
+    pub max_items: Option<usize>,
+    /// 已见过的画廊（`gallery_key`，无法识别时为 URL），新画廊以此为准
+    #[serde(default)]
+    pub seen: HashSet<String>,
+}
+
+impl Subscription {
+    /// 是否到了下一次检查时间
+    pub fn is_due(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let Some(last) = self
+            .last_checked_at
+            .as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        else {
+            return true;
+        };
+        let interval = chrono::Duration::minutes(self.interval_minutes.max(MIN_INTERVAL_MINUTES) as i64);
+        now.signed_duration_since(last.with_timezone(&chrono::Utc)) >= interval
+    }
+
+    /// 检查时的抓取范围：只读前几页和有限数量的画廊，遇到整页都已见过时不再翻页
+    pub fn listing_limit(&self) -> ListingLimit {
+        ListingLimit {
+            end_page: Some(MAX_CHECK_PAGES),
+            max_items: Some(self.max_items.unwrap_or(DEFAULT_MAX_ITEMS).max(1)),
+            known: self.seen.clone(),
+            ..ListingLimit::default()
+        }
+    }
+}
+
+/// 画廊在订阅中的去重键
+pub fn seen_key(url: &str) -> String {
+    crawler::gallery_key(url).unwrap_or_else(|| url.to_string())
+}
+
+/// 持久化的订阅列表，与下载历史放在同一目录
+#[derive(Clone, Default)]
+pub struct SubscriptionStore {
+    path: Option<PathBuf>,
+    subscriptions: Vec<Subscription>,
+    /// 正在检查的订阅，避免定时检查与添加、手动检查同时进行
+    checking: HashSet<String>,
+}
+
+impl SubscriptionStore {
+    pub fn set_dir_from_app(&mut self, app: &tauri::AppHandle) -> anyhow::Result<()> {
+        #[allow(deprecated)]
+        let base = app
+            .path()
+            .app_data_dir()
+            .unwrap_or(std::env::temp_dir());
+        self.set_dir(base);
+        Ok(())
+    }
+
+    /// 设置存储目录并加载已有订阅
+    pub fn set_dir(&mut self, dir: PathBuf) {
+        self.path = Some(dir.join(SUBSCRIPTIONS_FILE));
+        let _ = self.load();
+    }
+
+    pub fn all(&self) -> Vec<Subscription> {
+        self.subscriptions.clone()
+    }
+
+    pub fn get(&self, id: &str) -> Option<Subscription> {
+        self.subscriptions.iter().find(|s| s.id == id).cloned()
+    }
+
+    /// 添加订阅，同一列表地址只保留一个
+    pub fn add(&mut self, url: String, name: String, interval_minutes: u64, max_items: Option<usize>) -> anyhow::Result<Subscription> {
+        if self.subscriptions.iter().any(|s| s.url == url) {
+            anyhow::bail!("已订阅该列表: {}", url);
+        }
+        let subscription = Subscription {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: if name.trim().is_empty() { url.clone() } else { name.trim().to_string() },
+            url,
+            interval_minutes: interval_minutes.max(MIN_INTERVAL_MINUTES),
+            enabled: true,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            last_checked_at: None,
+            last_error: None,
+            last_new_count: 0,
+            max_items,
+            seen: HashSet::new(),
+        };
+        self.subscriptions.push(subscription.clone());
+        self.save()?;
+        Ok(subscription)
+    }
+
+    pub fn remove(&mut self, id: &str) -> anyhow::Result<bool> {
+        let before = self.subscriptions.len();
+        self.subscriptions.retain(|s| s.id != id);
+        let removed = self.subscriptions.len() != before;
+        if removed {
+            self.save()?;
+        }
+        Ok(removed)
+    }
+
+    /// 修改检查间隔或启用状态
+    pub fn update(&mut self, id: &str, interval_minutes: Option<u64>, enabled: Option<bool>) -> anyhow::Result<Subscription> {
+        let subscription = self
+            .subscriptions
+            .iter_mut()
+            .find(|s| s.id == id)
+            .ok_or_else(|| anyhow::anyhow!("订阅不存在: {}", id))?;
+        if let Some(minutes) = interval_minutes {
+            subscription.interval_minutes = minutes.max(MIN_INTERVAL_MINUTES);
+        }
+        if let Some(enabled) = enabled {
+            subscription.enabled = enabled;
+        }
+        let updated = subscription.clone();
+        self.save()?;
+        Ok(updated)
+    }
+
+    /// 到期需要检查的订阅，正在检查的除外
+    pub fn due(&self, now: chrono::DateTime<chrono::Utc>) -> Vec<Subscription> {
+        self.subscriptions
+            .iter()
+            .filter(|s| s.is_due(now) && !self.checking.contains(&s.id))
+            .cloned()
+            .collect()
+    }
+
+    /// 标记订阅开始检查，已在检查中时返回 false
+    pub fn begin_check(&mut self, id: &str) -> bool {
+        self.checking.insert(id.to_string())
+    }
+
+    pub fn end_check(&mut self, id: &str) {
+        self.checking.remove(id);
+    }
+
+    /// 记录一次成功的检查，返回此前未见过的画廊（保持列表顺序）
+    ///
+    /// 首次检查只记录现有画廊作为基线，除非 `include_existing` 为 true。
+    /// 返回的画廊不会标记为已见，创建任务后再调用 [`Self::mark_seen`]，入队失败的画廊下次检查会再次返回。
+    pub fn record_items(&mut self, id: &str, items: Vec<BatchItem>, include_existing: bool) -> anyhow::Result<Vec<BatchItem>> {
+        let subscription = self
+            .subscriptions
+            .iter_mut()
+            .find(|s| s.id == id)
+            .ok_or_else(|| anyhow::anyhow!("订阅不存在: {}", id))?;
+        // 以是否已有记录判断首次检查，首次检查失败不会影响基线
+        let baseline = subscription.seen.is_empty() && !include_existing;
+        let mut keys = HashSet::new();
+        let mut new_items = Vec::new();
+        for item in items {
+            let key = seen_key(&item.url);
+            if subscription.seen.contains(&key) || !keys.insert(key.clone()) {
+                continue;
+            }
+            if baseline {
+                subscription.seen.insert(key);
+            } else {
+                new_items.push(item);
+            }
+        }
+        subscription.last_checked_at = Some(chrono::Utc::now().to_rfc3339());
+        subscription.last_error = None;
+        subscription.last_new_count = new_items.len();
+        self.save()?;
+        Ok(new_items)
+    }
+
+    /// 将已创建任务（或已下载过）的画廊标记为已见
+    pub fn mark_seen(&mut self, id: &str, urls: &[String]) -> anyhow::Result<()> {
+        if urls.is_empty() {
+            return Ok(());
+        }
+        if let Some(subscription) = self.subscriptions.iter_mut().find(|s| s.id == id) {
+            subscription.seen.extend(urls.iter().map(|url| seen_key(url)));
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// 记录一次失败的检查，到下一个间隔再重试
+    pub fn record_error(&mut self, id: &str, error: String) -> anyhow::Result<()> {
+        if let Some(subscription) = self.subscriptions.iter_mut().find(|s| s.id == id) {
+            subscription.last_checked_at = Some(chrono::Utc::now().to_rfc3339());
+            subscription.last_error = Some(error);
+            subscription.last_new_count = 0;
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = self.path.as_ref() else { return Ok(()); };
+        if let Some(p) = path.parent() { fs::create_dir_all(p)?; }
+        let data = serde_json::to_string_pretty(&self.subscriptions)?;
+        // 先写临时文件再替换，避免写入中途退出导致文件损坏
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, data)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    fn load(&mut self) -> anyhow::Result<()> {
+        let Some(path) = self.path.as_ref() else { return Ok(()); };
+        if !path.exists() { return Ok(()); }
+        let data = fs::read_to_string(path)?;
+        self.subscriptions = serde_json::from_str(&data).unwrap_or_default();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(urls: &[&str]) -> Vec<BatchItem> {
+        urls.iter().map(|u| BatchItem::from_url(u.to_string())).collect()
+    }
+
+    #[test]
+    fn first_check_records_baseline_then_reports_only_new_galleries() {
+        let mut store = SubscriptionStore::default();
+        let sub = store.add("https://nhentai.net/artist/foo/".to_string(), String::new(), 60, None).unwrap();
+
+        let first = store.record_items(&sub.id, items(&["https://nhentai.net/g/1/", "https://nhentai.net/g/2/"]), false).unwrap();
+        let second = store
+            .record_items(&sub.id, items(&["https://nhentai.net/g/3/", "https://nhentai.net/g/1/"]), false)
+            .unwrap();
+
+        assert!(first.is_empty());
+        assert_eq!(second, items(&["https://nhentai.net/g/3/"]));
+        assert_eq!(store.get(&sub.id).unwrap().last_new_count, 1);
+    }
+
+    #[test]
+    fn new_galleries_stay_unseen_until_marked() {
+        let mut store = SubscriptionStore::default();
+        let sub = store.add("https://nhentai.net/artist/foo/".to_string(), String::new(), 60, None).unwrap();
+        store.record_items(&sub.id, items(&["https://nhentai.net/g/1/"]), false).unwrap();
+
+        let listing = items(&["https://nhentai.net/g/2/", "https://nhentai.net/g/3/", "https://nhentai.net/g/1/"]);
+        let failed = store.record_items(&sub.id, listing.clone(), false).unwrap();
+        store.mark_seen(&sub.id, &["https://nhentai.net/g/2/".to_string()]).unwrap();
+        let retried = store.record_items(&sub.id, listing, false).unwrap();
+
+        assert_eq!(failed, items(&["https://nhentai.net/g/2/", "https://nhentai.net/g/3/"]));
+        assert_eq!(retried, items(&["https://nhentai.net/g/3/"]));
+    }
+
+    #[test]
+    fn due_respects_interval_and_enabled_flag() {
+        let mut store = SubscriptionStore::default();
+        let sub = store.add("https://e-hentai.org/tag/x".to_string(), "x".to_string(), 1, None).unwrap();
+        let now = chrono::Utc::now();
+
+        assert_eq!(store.get(&sub.id).unwrap().interval_minutes, MIN_INTERVAL_MINUTES);
+        assert_eq!(store.due(now).len(), 1);
+
+        store.record_items(&sub.id, Vec::new(), false).unwrap();
+        let checked = chrono::Utc::now();
+        assert!(store.due(checked).is_empty());
+        assert_eq!(store.due(checked + chrono::Duration::minutes(MIN_INTERVAL_MINUTES as i64)).len(), 1);
+
+        store.update(&sub.id, None, Some(false)).unwrap();
+        assert!(store.due(checked + chrono::Duration::days(1)).is_empty());
+    }
+
+    #[test]
+    fn subscription_being_checked_is_not_due() {
+        let mut store = SubscriptionStore::default();
+        let sub = store.add("https://nhentai.net/artist/foo/".to_string(), String::new(), 60, None).unwrap();
+        let now = chrono::Utc::now();
+
+        assert!(store.begin_check(&sub.id));
+        assert!(!store.begin_check(&sub.id));
+        assert!(store.due(now).is_empty());
+
+        store.end_check(&sub.id);
+        assert_eq!(store.due(now).len(), 1);
+    }
+
+    #[test]
+    fn persists_subscriptions_across_reloads() {
+        let dir = std::env::temp_dir().join(format!(
+            "hmanga-subscriptions-test-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        let mut store = SubscriptionStore::default();
+        store.set_dir(dir.clone());
+        let sub = store.add("https://e-hentai.org/uploader/x".to_string(), "x".to_string(), 30, None).unwrap();
+        store.record_items(&sub.id, items(&["https://e-hentai.org/g/1/abc/"]), false).unwrap();
+        store.mark_seen(&sub.id, &["https://e-hentai.org/g/2/def/".to_string()]).unwrap();
+
+        let mut reloaded = SubscriptionStore::default();
+        reloaded.set_dir(dir.clone());
+        let _ = fs::remove_dir_all(&dir);
+
+        let restored = reloaded.get(&sub.id).unwrap();
+        assert_eq!(restored.interval_minutes, 30);
+        assert_eq!(restored.seen.len(), 2);
+    }
+}
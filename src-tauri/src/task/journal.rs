@@ -0,0 +1,123 @@
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::Manager as TauriManager;
+
+use super::{Task, TaskStatus};
+
+const JOURNAL_FILE: &str = "task_journal.json";
+
+/// 未完成任务日志
+///
+/// 与 `download_history.json` 放在同一目录，只记录排队/解析/下载中的任务，
+/// 应用重启后据此恢复中断的任务。
+#[derive(Clone, Default)]
+pub struct TaskJournal {
+    path: Arc<RwLock<Option<PathBuf>>>,
+}
+
+impl TaskJournal {
+    pub fn set_dir_from_app(&self, app: &tauri::AppHandle) -> anyhow::Result<()> {
+        #[allow(deprecated)]
+        let base = app
+            .path()
+            .app_data_dir()
+            .unwrap_or(std::env::temp_dir());
+        self.set_dir(base);
+        Ok(())
+    }
+
+    pub fn set_dir(&self, dir: PathBuf) {
+        *self.path.write() = Some(dir.join(JOURNAL_FILE));
+    }
+
+    /// 将当前未完成的任务写入日志（未设置目录时忽略）
+    pub fn save(&self, tasks: &HashMap<String, Task>) -> anyhow::Result<()> {
+        let Some(path) = self.path.read().clone() else { return Ok(()); };
+        let mut unfinished: Vec<&Task> = tasks.values().filter(|t| is_unfinished(t)).collect();
+        unfinished.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+        if let Some(p) = path.parent() { fs::create_dir_all(p)?; }
+        let data = serde_json::to_string_pretty(&unfinished)?;
+        // 先写临时文件再替换，避免写入中途退出导致日志损坏
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, data)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    /// 读取日志中记录的未完成任务；日志无法解析时备份原文件后返回错误，下次保存写入新日志
+    pub fn load(&self) -> anyhow::Result<Vec<Task>> {
+        let Some(path) = self.path.read().clone() else { return Ok(vec![]); };
+        if !path.exists() { return Ok(vec![]); }
+        let data = fs::read_to_string(&path)?;
+        let tasks: Vec<Task> = match serde_json::from_str(&data) {
+            Ok(tasks) => tasks,
+            Err(e) => {
+                let backup = path.with_extension("json.corrupt");
+                fs::rename(&path, &backup)?;
+                anyhow::bail!("任务日志无法解析，已备份为 {}: {}", backup.display(), e);
+            }
+        };
+        Ok(tasks.into_iter().filter(is_unfinished).collect())
+    }
+}
+
+fn is_unfinished(t: &Task) -> bool {
+    matches!(t.status, TaskStatus::Queued | TaskStatus::Parsing | TaskStatus::Running)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_keeps_only_unfinished_tasks_and_load_restores_them() {
+        let unique_dir = std::env::temp_dir().join(format!(
+            "hmanga-journal-test-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        let journal = TaskJournal::default();
+        journal.set_dir(unique_dir.clone());
+
+        let mut tasks = HashMap::new();
+        for (id, status) in [
+            ("running", TaskStatus::Running),
+            ("queued", TaskStatus::Queued),
+            ("completed", TaskStatus::Completed),
+            ("failed", TaskStatus::Failed),
+        ] {
+            tasks.insert(
+                id.to_string(),
+                Task { id: id.to_string(), status, ..Task::default() },
+            );
+        }
+
+        journal.save(&tasks).unwrap();
+        let mut restored = journal.load().unwrap();
+        let _ = std::fs::remove_dir_all(unique_dir);
+
+        restored.sort_by(|a, b| a.id.cmp(&b.id));
+        let ids: Vec<&str> = restored.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["queued", "running"]);
+    }
+
+    #[test]
+    fn corrupt_journal_is_moved_aside() {
+        let unique_dir = std::env::temp_dir().join(format!(
+            "hmanga-journal-corrupt-test-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        fs::create_dir_all(&unique_dir).unwrap();
+        fs::write(unique_dir.join(JOURNAL_FILE), "{not json").unwrap();
+        let journal = TaskJournal::default();
+        journal.set_dir(unique_dir.clone());
+
+        assert!(journal.load().is_err());
+        assert!(unique_dir.join("task_journal.json.corrupt").exists());
+        assert!(journal.load().unwrap().is_empty());
+
+        let _ = fs::remove_dir_all(&unique_dir);
+    }
+}
@@ -9,6 +9,7 @@ use crate::history;
 use crate::request::Client as RequestClient;
 use reqwest::header::HeaderMap;
 
+use super::journal::TaskJournal;
 use super::{FailedFile, Progress, Task, TaskStatus};
 
 /// Parameters for starting a batch download task
@@ -22,6 +23,7 @@ pub struct BatchDownloadParams {
     pub token_opt: Option<CancellationToken>,
     pub default_headers: Option<HeaderMap>,
     pub concurrency_override: Option<usize>,
+    pub image_transform: Option<crate::download::transform::ImageTransform>,
 }
 
 #[derive(Clone)]
@@ -29,6 +31,7 @@ pub struct TaskManager {
     pub tasks: Arc<RwLock<HashMap<String, Task>>>,
     pub download_concurrency: usize,
     pub max_concurrent_tasks: usize,
+    pub journal: TaskJournal,
 }
 
 impl Default for TaskManager {
@@ -38,6 +41,7 @@ impl Default for TaskManager {
             tasks: Arc::new(RwLock::new(HashMap::new())),
             download_concurrency: 8,
             max_concurrent_tasks,
+            journal: TaskJournal::default(),
         }
     }
 }
@@ -81,16 +85,69 @@ fn now_str() -> String {
 }
 
 impl TaskManager {
-    pub fn create_or_start(&self, task_id: &str, url: &str, total: i32) {
+    /// 将未完成任务写入日志
+    pub fn persist(&self) {
+        if let Err(e) = self.journal.save(&self.tasks.read()) {
+            tracing::warn!(error = %e, "failed to persist task journal");
+        }
+    }
+
+    /// 从日志恢复上次未完成的任务，统一置为排队状态等待队列处理器重新执行
+    pub fn restore_unfinished(&self) -> usize {
+        let restored = match self.journal.load() {
+            Ok(v) => v,
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to load task journal");
+                return 0;
+            }
+        };
+        let count = restored.len();
+        {
+            let mut w = self.tasks.write();
+            for mut t in restored {
+                t.status = TaskStatus::Queued;
+                t.progress = Progress::default();
+                t.failed_count = 0;
+                t.failed_files.clear();
+                t.error.clear();
+                t.resumed = true;
+                t.updated_at = now_str();
+                w.entry(t.id.clone()).or_insert(t);
+            }
+        }
+        self.persist();
+        count
+    }
+
+    pub fn create_queued(&self, task_id: &str, url: &str, chapters: &[usize]) {
+        {
+            let mut w = self.tasks.write();
+            let mut t = w.remove(task_id).unwrap_or_default();
+            t.id = task_id.to_string();
+            t.url = url.to_string();
+            t.chapters = chapters.to_vec();
+            t.status = TaskStatus::Queued;
+            t.progress = Progress { current: 0, total: 0 };
+            t.start_time = now_str();
+            t.updated_at = t.start_time.clone();
+            w.insert(task_id.to_string(), t);
+        }
+        self.persist();
+    }
+
+    pub fn create_or_start(&self, task_id: &str, url: &str, chapters: &[usize], total: i32) {
         let mut w = self.tasks.write();
         let mut t = w.remove(task_id).unwrap_or_default();
         t.id = task_id.to_string();
         t.url = url.to_string();
+        t.chapters = chapters.to_vec();
         t.status = TaskStatus::Parsing;
         t.progress = Progress { current: 0, total };
         t.start_time = now_str();
         t.updated_at = t.start_time.clone();
         w.insert(task_id.to_string(), t);
+        drop(w);
+        self.persist();
     }
 
     pub fn set_status_downloading(&self, task_id: &str, total: i32) {
@@ -100,6 +157,8 @@ impl TaskManager {
             t.progress.total = total;
             t.updated_at = now_str();
         }
+        drop(w);
+        self.persist();
     }
 
     pub fn set_name_and_path(&self, task_id: &str, name: &str, save_path: &str) {
@@ -109,6 +168,8 @@ impl TaskManager {
             t.save_path = save_path.to_string();
             t.updated_at = now_str();
         }
+        drop(w);
+        self.persist();
     }
 
     pub fn set_name(&self, task_id: &str, name: &str) {
@@ -127,6 +188,22 @@ impl TaskManager {
             t.complete_time = now_str();
             t.updated_at = t.complete_time.clone();
         }
+        drop(w);
+        self.persist();
+    }
+
+    /// 标记为重复跳过，`save_path` 指向已存在的画廊
+    pub fn set_skipped(&self, task_id: &str, reason: &str, save_path: &str) {
+        let mut w = self.tasks.write();
+        if let Some(t) = w.get_mut(task_id) {
+            t.status = TaskStatus::Skipped;
+            t.error = reason.to_string();
+            t.save_path = save_path.to_string();
+            t.complete_time = now_str();
+            t.updated_at = t.complete_time.clone();
+        }
+        drop(w);
+        self.persist();
     }
 
     pub fn set_cancelled(&self, task_id: &str) {
@@ -136,6 +213,8 @@ impl TaskManager {
             t.complete_time = now_str();
             t.updated_at = t.complete_time.clone();
         }
+        drop(w);
+        self.persist();
     }
 
 
@@ -195,6 +274,8 @@ impl TaskManager {
             if t.status == TaskStatus::Queued {
                 t.status = TaskStatus::Parsing;
                 t.updated_at = now_str();
+                drop(w);
+                self.persist();
                 return true;
             }
         }
@@ -211,9 +292,30 @@ impl TaskManager {
         use futures_util::StreamExt;
         let concurrency = params.concurrency_override.unwrap_or(self.download_concurrency);
         // 将请求客户端的限流与期望并发对齐，避免内部信号量限制导致并发达不到预期
-        let client = params.client.with_limit(concurrency);
+        // 站点配置的重试次数交给下载器（可续传），请求层不再重复重试
+        let default_config = DownloadConfig::default();
+        let validation = default_config.validation.clone();
+        let download_config = DownloadConfig {
+            retry_count: params.client.retry_count().unwrap_or(default_config.retry_count),
+            ..default_config
+        };
+        // 任务内并发由 buffer_unordered 控制，跨任务的总连接数与单主机连接数由共享的连接调度器控制
+        let client = params
+            .client
+            .with_limit(concurrency)
+            .with_retry_count(None)
+            .for_task(&params.task_id);
+        // 按画廊地址（而非图片 CDN）识别站点，用于按站点限速
+        let (site, resumed) = self
+            .tasks
+            .read()
+            .get(&params.task_id)
+            .map(|t| (crate::crawler::site_type_for_url(&t.url), t.resumed))
+            .unwrap_or_default();
         let downloader =
-            Downloader::new_with_headers(client, DownloadConfig::default(), params.default_headers);
+            Downloader::new_with_headers(client, download_config, params.default_headers)
+                .with_transform(params.image_transform)
+                .with_site(site);
         let token = params.token_opt.unwrap_or_default();
         let total = params.urls.len() as i32;
         let indices = params
@@ -230,20 +332,34 @@ impl TaskManager {
             t.error.clear();
             t.updated_at = now_str();
         }
+        self.persist();
         let ct = token.clone();
         let tm = self.tasks.clone();
+        let journal = self.journal.clone();
         tauri::async_runtime::spawn(async move {
             #[allow(clippy::useless_conversion)]
             let mut stream = stream::iter(params.urls.into_iter().zip(params.paths.into_iter()).zip(indices.into_iter()).map(|((u, p), index)| {
                 let d = downloader.clone();
                 let cancel = ct.clone();
+                let validation = validation.clone();
                 async move {
                     let path = p.to_string_lossy().to_string();
                     if cancel.is_cancelled() {
                         return (index, u, path, Err("cancelled".to_string()));
                     }
+                    // 从日志恢复的任务只补齐缺失或无效的页面，其他下载一律重新获取
+                    if resumed {
+                        let planned = p.clone();
+                        let existing = tokio::task::spawn_blocking(move || crate::download::find_valid_download(&planned, &validation))
+                            .await
+                            .ok()
+                            .flatten();
+                        if existing.is_some() {
+                            return (index, u, path, Ok(()));
+                        }
+                    }
 
-                    let res = d.download_file(&u, &p).await.map_err(|e| e.to_string());
+                    let res = d.download_file(&u, &p).await.map(|_| ()).map_err(|e| e.to_string());
                     (index, u, path, res)
                 }
             }))
@@ -291,6 +407,7 @@ impl TaskManager {
                     } else {
                         t.status = TaskStatus::PartialFailed;
                     }
+                    t.resumed = false;
                     t.complete_time = now_str();
                     t.updated_at = t.complete_time.clone();
                     status_str = match t.status {
@@ -302,6 +419,7 @@ impl TaskManager {
                         TaskStatus::PartialFailed => "partial_failed".to_string(),
                         TaskStatus::Failed => "failed".to_string(),
                         TaskStatus::Cancelled => "cancelled".to_string(),
+                        TaskStatus::Skipped => "skipped".to_string(),
                     };
                     error_msg = if t.failed_count > 0 {
                         format!("下载失败 {}/{}。{}", t.failed_count, t.progress.total, t.error)
@@ -326,8 +444,12 @@ impl TaskManager {
                             total: t.progress.total,
                         },
                         retryable: t.retryable,
+                        chapters: t.chapters.clone(),
                     };
                     drop(w);
+                    if let Err(e) = journal.save(&tm.read()) {
+                        tracing::warn!(error = %e, "failed to persist task journal");
+                    }
                     let mut hm = history::Manager::default();
                     let _ = hm.set_dir_from_app(&params.app);
                     hm.add_record(dto);
@@ -370,9 +492,12 @@ impl TaskManager {
             task.failed_count = 0;
             task.failed_files.clear();
             task.error = String::new();
+            task.resumed = false;
             task.last_retry_time = now_str();
             task.updated_at = now_str();
         }
+        drop(w);
+        self.persist();
     }
 
     /// 重置失败文件以便重试（仅重试失败的文件）
@@ -388,6 +513,8 @@ impl TaskManager {
                 task.status = TaskStatus::Running;
                 task.last_retry_time = now_str();
                 task.updated_at = now_str();
+                drop(w);
+                self.persist();
                 Ok(())
             } else {
                 Err("任务状态不是PartialFailed，无法重置失败文件".to_string())
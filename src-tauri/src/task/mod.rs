@@ -1,5 +1,6 @@
 pub mod model;
 pub mod manager;
+pub mod journal;
 
 pub use model::{FailedFile, Progress, Task, TaskStatus, TaskStatusInfo};
 pub use manager::TaskManager;
@@ -10,6 +10,7 @@ pub enum TaskStatus {
     #[serde(rename = "partial_failed")] PartialFailed,
     #[serde(rename = "failed")] Failed,
     #[serde(rename = "cancelled")] Cancelled,
+    #[serde(rename = "skipped")] Skipped,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -51,6 +52,12 @@ pub struct Task {
     pub updated_at: String,
     pub last_retry_time: String,
     pub retryable: bool,
+    /// 多章节画廊选择下载的章节（从 1 开始），为空表示全部
+    #[serde(default)]
+    pub chapters: Vec<usize>,
+    /// 从未完成任务日志恢复，下载时跳过磁盘上已校验通过的页面
+    #[serde(default)]
+    pub resumed: bool,
 }
 
 impl Default for Task {
@@ -70,6 +77,8 @@ impl Default for Task {
             updated_at: String::new(),
             last_retry_time: String::new(),
             retryable: true,
+            chapters: Vec::new(),
+            resumed: false,
         }
     }
 }
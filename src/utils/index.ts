@@ -1,4 +1,4 @@
-import { convertFileSrc } from "@tauri-apps/api/core";
+import { convertFileSrc, invoke } from "@tauri-apps/api/core";
 
 export function debounce(fn: (...args: any[]) => void, delay: number) {
     let timer: number | null = null;
@@ -23,3 +23,18 @@ export function toImgSrc(path: string): string {
     const normalized = path.replace(/\\/g, '/')
     return convertFileSrc(normalized)
 }
+
+// 压缩包内页面引用的分隔符，与后端 library::archive::PAGE_SEPARATOR 一致：`D:/manga/a.cbz!/0001.jpg`
+const ARCHIVE_PAGE_RE = /\.(cbz|zip)!\//i;
+
+export function isArchivePage(path: string): boolean {
+    return ARCHIVE_PAGE_RE.test(path)
+}
+
+export async function loadImgSrc(path: string): Promise<string> {
+    // 压缩包内页面无法通过 asset 协议访问，由后端读取为 data URL
+    if (isArchivePage(path)) {
+        return invoke<string>("library_read_page", { page: path })
+    }
+    return toImgSrc(path)
+}
@@ -2,7 +2,7 @@ import { invoke } from '@tauri-apps/api/core';
 import type { Manga } from '../stores/homeStore';
 import { useHomeStore } from '../stores/homeStore';
 import { resolveResource } from '@tauri-apps/api/path';
-import { toImgSrc } from '@/utils';
+import { loadImgSrc } from '@/utils';
 
 export class MangaService {
   private homeStore: ReturnType<typeof useHomeStore>;
@@ -42,8 +42,12 @@ export class MangaService {
       const imagePath = manga.previewImg;
       if (!imagePath) continue;
       if (!imageCache.has(imagePath)) {
-        const realUrl = toImgSrc(imagePath);
-        imageCache.set(imagePath, realUrl);
+        try {
+          const realUrl = await loadImgSrc(imagePath);
+          imageCache.set(imagePath, realUrl);
+        } catch (error) {
+          console.error(`加载预览图失败: ${imagePath}`, error);
+        }
       }
     }
     this.homeStore.mangaImages = imageCache;
@@ -54,7 +58,8 @@ export class MangaService {
    * 删除漫画
    */
   async deleteManga(manga: Manga): Promise<boolean> {
-    if (!confirm(`确定要删除 "${manga.name}" 吗？这将永久删除该文件夹及其内容！`)) {
+    const target = /\.(cbz|zip)$/i.test(manga.path) ? '该压缩包文件' : '该文件夹及其内容';
+    if (!confirm(`确定要删除 "${manga.name}" 吗？这将永久删除${target}！`)) {
       return false;
     }
 
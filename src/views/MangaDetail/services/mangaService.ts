@@ -5,7 +5,7 @@ import { useMangaStore } from "../stores";
 import { ProgressService } from "./progressService";
 import type { ScrollService } from "./scrollService";
 import { resolveResource } from "@tauri-apps/api/path";
-import { toImgSrc } from "@/utils";
+import { loadImgSrc } from "@/utils";
 import { nextTick } from "vue";
 
 export class MangaService {
@@ -68,7 +68,7 @@ export class MangaService {
       // 并行加载所有图片，保持顺序
       const imagePromises = imagePaths.map(async (imagePath) => {
         try {
-          const realUrl = toImgSrc(imagePath);
+          const realUrl = await loadImgSrc(imagePath);
           return realUrl;
         } catch (error) {
           console.error(`加载图片失败: ${imagePath}`, error);